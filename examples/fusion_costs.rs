@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright 2025 Ethan Jaszewski
+
+//! Measures the per-bit exponentiation and per-word division costs used to
+//! plan fused evaluation, for 64-bit and 128-bit moduli. Each cost is the
+//! difference between two sizes, so setting up the modulus cancels out.
+//!
+//! Run with `cargo run --release --example fusion_costs`.
+
+use std::{hint::black_box, time::Instant};
+
+use droplet::{
+    modular::{Modulus128, Reciprocal},
+    wide::{Fraction, Sum},
+};
+
+const MODULI: u64 = 100_000;
+
+/// Number of passes over the moduli, of which the fastest is kept.
+const PASSES: usize = 5;
+
+/// Average nanoseconds per call of `f` over every modulus, in the fastest
+/// of [`PASSES`] passes.
+fn time<M: Copy>(moduli: &[M], mut f: impl FnMut(M)) -> f64 {
+    (0..PASSES)
+        .map(|_| {
+            let start = Instant::now();
+            for &modulus in moduli {
+                f(black_box(modulus));
+            }
+            start.elapsed().as_nanos() as f64 / moduli.len() as f64
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn pow_costs(narrow: &[u64], wide: &[u128], bits: u32) -> (f64, f64) {
+    let exponent = (1u64 << bits) - 1;
+    let narrow = time(narrow, |modulus| {
+        black_box(Reciprocal::new(modulus).mod_pow_init(1, 2, exponent));
+    });
+    let wide = time(wide, |modulus| {
+        black_box(Modulus128::new(modulus).mod_pow2_init(1, exponent));
+    });
+    (narrow, wide)
+}
+
+fn div_costs<const D: usize>(narrow: &[u64], wide: &[u128]) -> (f64, f64) {
+    let narrow = time(narrow, |modulus| {
+        let reciprocal = Reciprocal::new(modulus);
+        black_box(<Sum<D> as Fraction>::from_msd(D, modulus / 3) / &reciprocal);
+    });
+    let wide = time(wide, |modulus| {
+        let modulus = Modulus128::new(modulus);
+        black_box(<Sum<D> as Fraction>::from_ratio(
+            D,
+            modulus.modulus() / 3,
+            &modulus,
+        ));
+    });
+    (narrow, wide)
+}
+
+fn main() {
+    let narrow: Vec<u64> = (0..MODULI).map(|i| (1 << 62) + 2 * i + 1).collect();
+    let wide: Vec<u128> = (0..MODULI)
+        .map(|i| (1 << 100) + 2 * u128::from(i) + 1)
+        .collect();
+
+    let (short, long) = (pow_costs(&narrow, &wide, 16), pow_costs(&narrow, &wide, 56));
+    println!(
+        "POW_BIT_COST:  [{:.1}, {:.1}]",
+        (long.0 - short.0) / 40.0,
+        (long.1 - short.1) / 40.0
+    );
+
+    let (short, long) = (
+        div_costs::<4>(&narrow, &wide),
+        div_costs::<16>(&narrow, &wide),
+    );
+    println!(
+        "DIV_WORD_COST: [{:.1}, {:.1}]",
+        (long.0 - short.0) / 12.0,
+        (long.1 - short.1) / 12.0
+    );
+}
@@ -0,0 +1,165 @@
+use crate::modular::FloatModulus;
+
+/// Number of independent exponentiations computed together.
+pub const LANES: usize = 8;
+
+/// Computes `inits[k] * 2^exponents[k] mod moduli[k]` for every lane.
+///
+/// Every modulus must be nonzero and below [`FloatModulus::LIMIT`], and every
+/// init below its modulus. Uses AVX2 and FMA when the CPU supports them, and
+/// [`FloatModulus`] one lane at a time otherwise. Both give identical results.
+pub fn mod_pow2_init(
+    inits: [u64; LANES],
+    exponents: [u64; LANES],
+    moduli: [u64; LANES],
+) -> [u64; LANES] {
+    debug_assert!(
+        (0..LANES).all(|lane| inits[lane] < moduli[lane] && moduli[lane] < FloatModulus::LIMIT)
+    );
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+        // SAFETY: the required target features were detected above
+        return unsafe { x86::mod_pow2_init(inits, exponents, moduli) };
+    }
+    mod_pow2_init_scalar(inits, exponents, moduli)
+}
+
+fn mod_pow2_init_scalar(
+    inits: [u64; LANES],
+    exponents: [u64; LANES],
+    moduli: [u64; LANES],
+) -> [u64; LANES] {
+    std::array::from_fn(|lane| {
+        FloatModulus::new(moduli[lane]).mod_pow_init(inits[lane], 2, exponents[lane])
+    })
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    use super::LANES;
+
+    const VECTORS: usize = LANES / 4;
+
+    /// Computes `lhs * rhs mod modulus` for residues below `modulus < 2^50`.
+    ///
+    /// The product is split exactly into `high + low` with a fused
+    /// multiply-subtract. The quotient estimate `⌊high / modulus⌋` is within
+    /// one of the true quotient, so after the exact remainder `high - q * m +
+    /// low` a single correction in each direction is enough.
+    #[target_feature(enable = "avx2,fma")]
+    fn multiply(lhs: __m256d, rhs: __m256d, modulus: __m256d, inverse: __m256d) -> __m256d {
+        let high = _mm256_mul_pd(lhs, rhs);
+        let low = _mm256_fmsub_pd(lhs, rhs, high);
+        let quotient = _mm256_floor_pd(_mm256_mul_pd(high, inverse));
+        let remainder = _mm256_add_pd(_mm256_fnmadd_pd(quotient, modulus, high), low);
+        let zero = _mm256_setzero_pd();
+        let negative = _mm256_cmp_pd::<_CMP_LT_OQ>(remainder, zero);
+        let remainder = _mm256_add_pd(remainder, _mm256_and_pd(negative, modulus));
+        let overflow = _mm256_cmp_pd::<_CMP_GE_OQ>(remainder, modulus);
+        _mm256_sub_pd(remainder, _mm256_and_pd(overflow, modulus))
+    }
+
+    #[target_feature(enable = "avx2,fma")]
+    fn double(value: __m256d, modulus: __m256d) -> __m256d {
+        let doubled = _mm256_add_pd(value, value);
+        let overflow = _mm256_cmp_pd::<_CMP_GE_OQ>(doubled, modulus);
+        _mm256_sub_pd(doubled, _mm256_and_pd(overflow, modulus))
+    }
+
+    #[target_feature(enable = "avx2,fma")]
+    fn load(values: &[u64; LANES], vector: usize) -> __m256d {
+        let lane = |idx: usize| values[4 * vector + idx] as i64 as f64;
+        _mm256_setr_pd(lane(0), lane(1), lane(2), lane(3))
+    }
+
+    #[target_feature(enable = "avx2,fma")]
+    pub(super) fn mod_pow2_init(
+        inits: [u64; LANES],
+        exponents: [u64; LANES],
+        moduli: [u64; LANES],
+    ) -> [u64; LANES] {
+        let moduli_v: [__m256d; VECTORS] = std::array::from_fn(|v| load(&moduli, v));
+        let inverses: [__m256d; VECTORS] =
+            std::array::from_fn(|v| _mm256_div_pd(_mm256_set1_pd(1.0), moduli_v[v]));
+        let exponents_v: [__m256i; VECTORS] = std::array::from_fn(|v| {
+            let lane = |idx: usize| exponents[4 * v + idx] as i64;
+            _mm256_setr_epi64x(lane(0), lane(1), lane(2), lane(3))
+        });
+        let one = _mm256_set1_epi64x(1);
+
+        // Left-to-right, so multiplying by the base is a modular doubling.
+        // Lanes with shorter exponents just square 1 until their bits start.
+        let max_exponent = exponents.iter().copied().max().unwrap_or(0);
+        let mut results = [_mm256_set1_pd(1.0); VECTORS];
+        for bit in (0..u64::BITS - max_exponent.leading_zeros()).rev() {
+            let count = _mm_cvtsi64_si128(i64::from(bit));
+            for v in 0..VECTORS {
+                let squared = multiply(results[v], results[v], moduli_v[v], inverses[v]);
+                let doubled = double(squared, moduli_v[v]);
+                let bits = _mm256_and_si256(_mm256_srl_epi64(exponents_v[v], count), one);
+                let mask = _mm256_castsi256_pd(_mm256_cmpeq_epi64(bits, one));
+                results[v] = _mm256_blendv_pd(squared, doubled, mask);
+            }
+        }
+
+        let mut output = [0f64; LANES];
+        for v in 0..VECTORS {
+            let inits_v = load(&inits, v);
+            let result = multiply(results[v], inits_v, moduli_v[v], inverses[v]);
+            // SAFETY: output has room for LANES = 4 * VECTORS values
+            unsafe { _mm256_storeu_pd(output.as_mut_ptr().add(4 * v), result) };
+        }
+        output.map(|value| value as i64 as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lanes_match_the_scalar_fallback() {
+        let mut state = 31u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for round in 0..2000 {
+            let moduli: [u64; LANES] = std::array::from_fn(|lane| match (round + lane) % 4 {
+                0 => FloatModulus::LIMIT - 1 - next() % 1000,
+                1 => 1 + next() % 100,
+                _ => 1 + next() % (FloatModulus::LIMIT - 1),
+            });
+            let inits = moduli.map(|modulus| next() % modulus);
+            let exponents: [u64; LANES] = std::array::from_fn(|lane| match lane {
+                0 => 0,
+                1 => next() % 64,
+                _ => next() >> (next() % 64),
+            });
+
+            let expected = mod_pow2_init_scalar(inits, exponents, moduli);
+            for lane in 0..LANES {
+                let reference =
+                    crate::modular::mod_pow_primitive::<u128>(2, exponents[lane], moduli[lane]);
+                let reference = (u128::from(reference) * u128::from(inits[lane])
+                    % u128::from(moduli[lane])) as u64;
+                assert_eq!(expected[lane], reference, "lane {lane} of round {round}");
+            }
+            assert_eq!(
+                mod_pow2_init(inits, exponents, moduli),
+                expected,
+                "round {round}"
+            );
+            #[cfg(target_arch = "x86_64")]
+            if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+                // SAFETY: the required target features were detected above
+                let lanes = unsafe { x86::mod_pow2_init(inits, exponents, moduli) };
+                assert_eq!(lanes, expected, "round {round}");
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright 2025 Ethan Jaszewski
+
+use std::time::Instant;
+
+use droplet::{digits::autotune, formulas, tune::Tuning};
+
+fn main() {
+    let hex_digits: u32 = match std::env::args().nth(1) {
+        Some(arg) => arg
+            .parse()
+            .unwrap_or_else(|err| usage(&format!("invalid hex digits {arg:?}: {err}"))),
+        None => 314,
+    };
+
+    let hex_digit = 3_141_592_650;
+    let start = Instant::now();
+    let tuning = autotune(formulas::bellards_pi(), hex_digit, hex_digits, 16)
+        .unwrap_or_else(|err| panic!("{err}"));
+    let time = start.elapsed();
+
+    let path = Tuning::path();
+    tuning
+        .save(&path)
+        .unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+    println!("Tuned to {tuning} in {time:?}, saved to {}", path.display());
+}
+
+/// Reports a bad command line and exits with status 2.
+fn usage(problem: &str) -> ! {
+    eprintln!("{problem}");
+    eprintln!("usage: autotune [hex digits]");
+    std::process::exit(2);
+}
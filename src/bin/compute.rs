@@ -3,25 +3,92 @@
 
 use std::time::Instant;
 
-use droplet::{formulas, wide::Sum};
+use droplet::{
+    checkpoint::{Checkpoint, CheckpointError},
+    digits::{DigitsError, digits_checkpointed, digits_monitored},
+    formulas,
+    modular::PowMethod,
+    progress::{CancelToken, Monitor, Progress},
+    tune::Tuning,
+};
 
 fn main() {
+    let pow_method: PowMethod = match std::env::args().nth(1) {
+        Some(arg) => arg
+            .parse()
+            .unwrap_or_else(|err| usage(&format!("invalid pow method {arg:?}: {err}"))),
+        None => Tuning::current().pow_method,
+    };
+    let hex_digits: u32 = match std::env::args().nth(2) {
+        Some(arg) => arg
+            .parse()
+            .unwrap_or_else(|err| usage(&format!("invalid hex digits {arg:?}: {err}"))),
+        None => 314,
+    };
+
     let hex_digit = 3_141_592_650;
-    let calc_digit = hex_digit / 5 * 2;
-    let bellards_formula = formulas::bellards_pi();
+    let bellards_formula = formulas::bellards_pi().with_pow_method(pow_method);
+
+    let monitor = Monitor::new()
+        .with_cancel(CancelToken::sigint())
+        .with_callback(draw_progress);
 
-    const WORDS: usize = 24;
     let start = Instant::now();
-    let digits: Sum<WORDS> = bellards_formula.evaluate_parallel(calc_digit, 24);
+    let digits = match std::env::args().nth(3) {
+        Some(path) => {
+            let checkpoint = Checkpoint::new(path).with_monitor(monitor);
+            digits_checkpointed(bellards_formula, hex_digit, hex_digits, 16, &checkpoint)
+        }
+        None => digits_monitored(bellards_formula, hex_digit, hex_digits, 16, &monitor),
+    };
+    let digits = match digits {
+        Ok(digits) => digits,
+        Err(
+            err @ (DigitsError::Cancelled | DigitsError::Checkpoint(CheckpointError::Interrupted)),
+        ) => {
+            // Ctrl-C, after which the progress bar is left mid-line
+            eprintln!("\n{err}");
+            std::process::exit(130);
+        }
+        Err(err) => panic!("{err}"),
+    };
     let time = start.elapsed();
 
-    let n_digits = (WORDS - 1) * 16 - (calc_digit.ilog2() as usize);
-    for (idx, word) in digits.digits[1..].iter().enumerate() {
-        if n_digits < 16 * idx {
-            break;
+    let hex = digits.to_string();
+    for (idx, row) in hex.as_bytes().chunks(16).enumerate() {
+        println!("{idx:02}: {}", String::from_utf8_lossy(row));
+    }
+
+    println!(
+        "Calcualted {hex_digits} digits at offset {hex_digit} in {time:?} using {pow_method:?}"
+    );
+}
+
+/// Redraws a progress bar and the time left over the current line of stderr.
+fn draw_progress(progress: &Progress) {
+    const WIDTH: usize = 40;
+    let filled = ((progress.fraction() * WIDTH as f64) as usize).min(WIDTH);
+    let eta = match progress.eta() {
+        Some(eta) => {
+            let secs = eta.as_secs();
+            format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
         }
-        println!("{idx:02}: {word:016x}");
+        None => String::from("?"),
+    };
+    eprint!(
+        "\r[{}{}] {:5.1}% ETA {eta}  ",
+        "#".repeat(filled),
+        " ".repeat(WIDTH - filled),
+        100.0 * progress.fraction()
+    );
+    if progress.done == progress.total {
+        eprintln!();
     }
+}
 
-    println!("Calcualted {n_digits} digits at offset {hex_digit} in {time:?}");
+/// Reports a bad command line and exits with status 2.
+fn usage(problem: &str) -> ! {
+    eprintln!("{problem}");
+    eprintln!("usage: compute [pow method] [hex digits] [checkpoint path]");
+    std::process::exit(2);
 }
\ No newline at end of file
@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright 2025 Ethan Jaszewski
+
+use std::time::Instant;
+
+use droplet::{digits::digits_with, distributed::Coordinator, formulas};
+
+fn main() {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| usage("missing listen address"));
+    let hex_digits: u32 = match std::env::args().nth(2) {
+        Some(arg) => arg
+            .parse()
+            .unwrap_or_else(|err| usage(&format!("invalid hex digits {arg:?}: {err}"))),
+        None => 314,
+    };
+
+    let hex_digit = 3_141_592_650;
+    let coordinator = Coordinator::bind(&address).unwrap_or_else(|err| panic!("{err}"));
+    println!("Waiting for workers on {address}");
+
+    let start = Instant::now();
+    let digits = digits_with(
+        formulas::bellards_pi(),
+        hex_digit,
+        hex_digits,
+        16,
+        |formula, digit, words, _| formula.evaluate_distributed(digit, words, &coordinator),
+    )
+    .unwrap_or_else(|err| panic!("{err}"));
+    let time = start.elapsed();
+
+    let hex = digits.to_string();
+    for (idx, row) in hex.as_bytes().chunks(16).enumerate() {
+        println!("{idx:02}: {}", String::from_utf8_lossy(row));
+    }
+
+    println!("Calcualted {hex_digits} digits at offset {hex_digit} in {time:?}");
+}
+
+/// Reports a bad command line and exits with status 2.
+fn usage(problem: &str) -> ! {
+    eprintln!("{problem}");
+    eprintln!("usage: coordinator <listen address> [hex digits]");
+    std::process::exit(2);
+}
@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright 2025 Ethan Jaszewski
+
+use std::io::Write;
+
+use droplet::formulas::Constant;
+
+fn main() {
+    let position: u64 = match std::env::args().nth(1) {
+        Some(arg) => arg
+            .parse()
+            .unwrap_or_else(|err| usage(&format!("invalid position {arg:?}: {err}"))),
+        None => 0,
+    };
+    let count: usize = match std::env::args().nth(2) {
+        Some(arg) => arg
+            .parse()
+            .unwrap_or_else(|err| usage(&format!("invalid count {arg:?}: {err}"))),
+        None => 1024,
+    };
+
+    let mut stdout = std::io::stdout();
+    for (idx, digit) in Constant::Pi.digits_from(position).take(count).enumerate() {
+        let digit = digit.unwrap_or_else(|err| panic!("{err}"));
+        let separator = if (idx + 1) % 64 == 0 { "\n" } else { "" };
+        write!(stdout, "{digit:x}{separator}").unwrap();
+        stdout.flush().unwrap();
+    }
+    writeln!(stdout).unwrap();
+}
+
+/// Reports a bad command line and exits with status 2.
+fn usage(problem: &str) -> ! {
+    eprintln!("{problem}");
+    eprintln!("usage: stream [position] [count]");
+    std::process::exit(2);
+}
@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: MPL-2.0
+// Copyright 2025 Ethan Jaszewski
+
+use std::thread;
+
+use droplet::{formulas, modular::PowMethod, tune::Tuning};
+
+fn main() {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| usage("missing coordinator address"));
+    let tuning = Tuning::current();
+    let threads: usize = match std::env::args().nth(2) {
+        Some(arg) => arg
+            .parse()
+            .unwrap_or_else(|err| usage(&format!("invalid threads {arg:?}: {err}"))),
+        None => tuning.threads,
+    };
+    let pow_method: PowMethod = match std::env::args().nth(3) {
+        Some(arg) => arg
+            .parse()
+            .unwrap_or_else(|err| usage(&format!("invalid pow method {arg:?}: {err}"))),
+        None => tuning.pow_method,
+    };
+
+    // Each thread connects on its own, so the coordinator balances the load
+    // across threads as well as machines
+    let bellards_formula = formulas::bellards_pi().with_pow_method(pow_method);
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                if let Err(err) = bellards_formula.work_for(&address) {
+                    eprintln!("Worker stopped: {err}");
+                }
+            });
+        }
+    });
+}
+
+/// Reports a bad command line and exits with status 2.
+fn usage(problem: &str) -> ! {
+    eprintln!("{problem}");
+    eprintln!("usage: worker <coordinator address> [threads] [pow method]");
+    std::process::exit(2);
+}
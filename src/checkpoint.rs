@@ -0,0 +1,487 @@
+use std::{
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    Evaluation, PolyFormula,
+    partial::Partial,
+    progress::{Monitor, Tracker},
+    wide::Fraction,
+};
+
+/// First line of every checkpoint file, naming the format version.
+const HEADER: &str = "droplet checkpoint 1";
+
+/// Where and how often [`PolyFormula::evaluate_checkpointed`] saves
+/// its progress.
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    path: PathBuf,
+    interval: Duration,
+    monitor: Monitor,
+}
+
+impl Checkpoint {
+    /// Saves to `path` once a minute.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            interval: Duration::from_secs(60),
+            monitor: Monitor::new(),
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Reports progress to `monitor`, and stops evaluating and saves as soon
+    /// as its token is cancelled.
+    pub fn with_monitor(mut self, monitor: Monitor) -> Self {
+        self.monitor = monitor;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The same checkpoint saving to its path with `suffix` appended.
+    pub(crate) fn with_suffix(&self, suffix: &str) -> Self {
+        let mut path = self.path.clone().into_os_string();
+        path.push(suffix);
+        Self {
+            path: path.into(),
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckpointError {
+    /// Reading or writing the file failed, with the kind and message of the
+    /// [`std::io::Error`].
+    Io {
+        kind: std::io::ErrorKind,
+        message: String,
+    },
+    /// The file is not a checkpoint this version can read.
+    Malformed(String),
+    /// The checkpoint is for a different computation.
+    Mismatch {
+        field: &'static str,
+        expected: String,
+        found: String,
+    },
+    /// Evaluation was stopped before it finished, with its progress saved.
+    Interrupted,
+}
+
+impl std::fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckpointError::Io { message, .. } => write!(f, "checkpoint I/O failed: {message}"),
+            CheckpointError::Malformed(line) => write!(f, "malformed checkpoint line: {line}"),
+            CheckpointError::Mismatch {
+                field,
+                expected,
+                found,
+            } => write!(f, "checkpoint is for {field} {found}, expected {expected}"),
+            CheckpointError::Interrupted => write!(f, "interrupted, progress saved"),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+impl From<std::io::Error> for CheckpointError {
+    fn from(err: std::io::Error) -> Self {
+        CheckpointError::Io {
+            kind: err.kind(),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl<const N_DEGREE: usize, const D_DEGREE: usize> PolyFormula<N_DEGREE, D_DEGREE> {
+    /// A single line naming every parameter that affects the value of an
+    /// evaluation, to check a checkpoint against.
+    pub(crate) fn identity(&self) -> String {
+        let terms: Vec<String> = self
+            .numerators
+            .iter()
+            .zip(&self.denominators)
+            .zip(&self.scales)
+            .map(|((numerator, denominator), scale)| {
+                format!("{numerator}/2^{scale}({denominator})")
+            })
+            .collect();
+        let sign = if self.alternating { "-" } else { "+" };
+        format!("{sign}{}: {}", 1u64 << self.base_log2, terms.join(" + "))
+    }
+
+    /// Evaluates at `digit` to `words` words like
+    /// [`PolyFormula::evaluate_bounded`], saving the indices summed so
+    /// far to `checkpoint` at its interval, when cancelled and when finished.
+    /// Resumes from the checkpoint file if there is one, provided it was
+    /// saved by an evaluation of the same formula at the same digit and
+    /// width.
+    pub fn evaluate_checkpointed<S: Fraction>(
+        &self,
+        digit: u32,
+        threads: usize,
+        words: usize,
+        checkpoint: &Checkpoint,
+    ) -> Result<Evaluation<S>, CheckpointError> {
+        let mut partial = match fs::read_to_string(&checkpoint.path) {
+            Ok(contents) => self.parse_checkpoint(&contents, digit, words)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Partial::zero(digit, words),
+            Err(err) => return Err(err.into()),
+        };
+        let threads = threads.max(1);
+        let tail_terms = self.tail(digit, words).terms;
+        let summed: u64 = partial.ranges.iter().map(|range| range.len() as u64).sum();
+        let resumed = summed + partial.tail.map_or(0, |tail| u64::from(tail.terms));
+        let total = u64::from(digit) + 1 + u64::from(tail_terms);
+        let tracker = Tracker::new(
+            &checkpoint.monitor,
+            total,
+            resumed,
+            self.numerators.len(),
+            threads,
+        );
+        if partial.tail.is_none() {
+            let tail = self.evaluate_tail(digit, words);
+            partial = partial.merge(tail).expect("checkpoint has no tail");
+            tracker.record(0, tail_terms.into());
+        }
+
+        let pending = pending_chunks(&partial.ranges, digit + 1, self.chunk);
+        let cursor = AtomicUsize::new(0);
+        let state = Mutex::new((partial, Instant::now()));
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|id| {
+                    let (tracker, cursor, pending, state) = (&tracker, &cursor, &pending, &state);
+                    scope.spawn(move || -> Result<(), CheckpointError> {
+                        while !tracker.cancelled() {
+                            let Some(range) = pending.get(cursor.fetch_add(1, Ordering::Relaxed))
+                            else {
+                                break;
+                            };
+                            let chunk = self
+                                .evaluate_range(digit, range.clone(), words)
+                                .expect("pending ranges stop at the digit");
+                            tracker.record(id, range.len() as u64);
+                            let mut state = state.lock().unwrap();
+                            let (partial, saved) = &mut *state;
+                            partial.merge_mut(chunk).expect("chunks are disjoint");
+                            if saved.elapsed() >= checkpoint.interval {
+                                self.save_checkpoint(partial, checkpoint)?;
+                                *saved = Instant::now();
+                            }
+                        }
+                        Ok(())
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .try_for_each(|handle| handle.join().unwrap())
+        })?;
+
+        tracker.finish();
+        let (partial, _) = state.into_inner().unwrap();
+        self.save_checkpoint(&partial, checkpoint)?;
+        partial
+            .into_evaluation()
+            .ok_or(CheckpointError::Interrupted)
+    }
+
+    fn save_checkpoint<S: Fraction>(
+        &self,
+        partial: &Partial<S>,
+        checkpoint: &Checkpoint,
+    ) -> std::io::Result<()> {
+        let ranges: Vec<String> = partial
+            .ranges
+            .iter()
+            .map(|range| format!("{}..{}", range.start, range.end))
+            .collect();
+        let value: Vec<String> = partial
+            .value
+            .digits()
+            .iter()
+            .map(|word| format!("{word:016x}"))
+            .collect();
+        let contents = [
+            HEADER.to_string(),
+            format!("formula {}", self.identity()),
+            format!("fusion {}", self.fusion),
+            format!("digit {}", partial.digit),
+            format!("words {}", partial.value.words()),
+            format!("tail {}", partial.tail.is_some()),
+            format!("divisions {}", partial.divisions),
+            format!("ranges {}", ranges.join(" ")),
+            format!("value {}", value.join(" ")),
+        ]
+        .join("\n");
+
+        // Write a new file and rename it over the old one, so a crash while
+        // saving leaves the previous checkpoint intact
+        let temporary = checkpoint.with_suffix(".tmp");
+        fs::write(&temporary.path, contents + "\n")?;
+        fs::rename(&temporary.path, &checkpoint.path)
+    }
+
+    fn parse_checkpoint<S: Fraction>(
+        &self,
+        contents: &str,
+        digit: u32,
+        words: usize,
+    ) -> Result<Partial<S>, CheckpointError> {
+        let mut lines = contents.lines();
+        let header = lines.next().unwrap_or_default();
+        if header != HEADER {
+            return Err(CheckpointError::Malformed(header.to_string()));
+        }
+        let mut field = |key: &'static str| -> Result<&str, CheckpointError> {
+            let line = lines.next().unwrap_or_default();
+            match line.split_once(' ').unwrap_or((line, "")) {
+                (found, value) if found == key => Ok(value),
+                _ => Err(CheckpointError::Malformed(line.to_string())),
+            }
+        };
+        let expect = |key: &'static str, found: &str, expected: String| {
+            if found == expected {
+                Ok(())
+            } else {
+                Err(CheckpointError::Mismatch {
+                    field: key,
+                    expected,
+                    found: found.to_string(),
+                })
+            }
+        };
+        let parse = |key: &str, value: &str| CheckpointError::Malformed(format!("{key} {value}"));
+
+        expect("formula", field("formula")?, self.identity())?;
+        expect("fusion", field("fusion")?, self.fusion.to_string())?;
+        expect("digit", field("digit")?, digit.to_string())?;
+        expect("words", field("words")?, words.to_string())?;
+
+        let tail = match field("tail")? {
+            "true" => Some(self.tail(digit, words)),
+            "false" => None,
+            value => return Err(parse("tail", value)),
+        };
+        let divisions = field("divisions")?;
+        let divisions = divisions
+            .parse()
+            .map_err(|_| parse("divisions", divisions))?;
+        let ranges = field("ranges")?
+            .split_whitespace()
+            .map(|range| {
+                let (start, end) = range
+                    .split_once("..")
+                    .ok_or_else(|| parse("ranges", range))?;
+                match (start.parse(), end.parse()) {
+                    (Ok(start), Ok(end)) => Ok(start..end),
+                    _ => Err(parse("ranges", range)),
+                }
+            })
+            .collect::<Result<Vec<Range<u32>>, _>>()?;
+        // Resuming relies on the ranges being kept as a partial sum keeps
+        // them, non-empty, sorted, apart from each other and up to the digit
+        for (idx, range) in ranges.iter().enumerate() {
+            let after_previous = idx == 0 || ranges[idx - 1].end < range.start;
+            if range.is_empty() || range.end > digit.saturating_add(1) || !after_previous {
+                return Err(parse("ranges", &format!("{}..{}", range.start, range.end)));
+            }
+        }
+        let mut value = S::zero(words);
+        let mut hex_words = field("value")?.split_whitespace();
+        for word in value.digits_mut() {
+            let hex = hex_words.next().ok_or_else(|| parse("value", ""))?;
+            *word = u64::from_str_radix(hex, 16).map_err(|_| parse("value", hex))?;
+        }
+
+        Ok(Partial {
+            digit,
+            value,
+            ranges,
+            tail,
+            divisions,
+        })
+    }
+}
+
+/// Splits the indices below `end` that are not in `done`, which is sorted and
+/// disjoint, into ranges of at most `chunk` indices.
+fn pending_chunks(done: &[Range<u32>], end: u32, chunk: u32) -> Vec<Range<u32>> {
+    let mut pending = Vec::new();
+    let mut start = 0;
+    for range in done.iter().chain([&(end..end)]) {
+        while start < range.start {
+            let chunk_end = start.saturating_add(chunk).min(range.start);
+            pending.push(start..chunk_end);
+            start = chunk_end;
+        }
+        start = start.max(range.end);
+    }
+    pending
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{formulas, wide::Sum};
+
+    fn checkpoint(name: &str) -> Checkpoint {
+        let path = std::env::temp_dir().join(format!(
+            "droplet-checkpoint-{name}-{}.txt",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        Checkpoint::new(path)
+    }
+
+    #[test]
+    fn resuming_matches_bounded_evaluation() {
+        let formula = formulas::bellards_pi().with_chunk(64);
+        let expected: Evaluation<Sum<3>> = formula.evaluate_bounded(1_000, 2, 3);
+
+        // Saved partway, with gaps before, between and after the ranges
+        let checkpoint = checkpoint("resume");
+        let partial = [100..300, 450..451, 700..1_000]
+            .into_iter()
+            .map(|range| formula.evaluate_range::<Sum<3>>(1_000, range, 3).unwrap())
+            .fold(Partial::zero(1_000, 3), |sum, range| {
+                sum.merge(range).unwrap()
+            });
+        formula.save_checkpoint(&partial, &checkpoint).unwrap();
+        let evaluation = formula
+            .evaluate_checkpointed::<Sum<3>>(1_000, 2, 3, &checkpoint)
+            .unwrap();
+        assert_eq!(evaluation.value.digits, expected.value.digits);
+        assert_eq!(evaluation.error, expected.error);
+
+        // The finished checkpoint answers at once
+        let monitor = Monitor::new();
+        monitor.cancel_token().cancel();
+        let finished = checkpoint.clone().with_monitor(monitor);
+        let evaluation = formula
+            .evaluate_checkpointed::<Sum<3>>(1_000, 2, 3, &finished)
+            .unwrap();
+        assert_eq!(evaluation.value.digits, expected.value.digits);
+        fs::remove_file(checkpoint.path()).unwrap();
+    }
+
+    #[test]
+    fn cancelled_evaluation_resumes() {
+        let formula = formulas::bellards_pi();
+        let expected: Evaluation<Sum<3>> = formula.evaluate_bounded(2_000, 1, 3);
+        let checkpoint = checkpoint("cancel");
+        let monitor = Monitor::new();
+        monitor.cancel_token().cancel();
+        let cancelled = checkpoint.clone().with_monitor(monitor);
+        assert_eq!(
+            formula
+                .evaluate_checkpointed::<Sum<3>>(2_000, 1, 3, &cancelled)
+                .unwrap_err(),
+            CheckpointError::Interrupted
+        );
+        let saved = fs::read_to_string(checkpoint.path()).unwrap();
+        assert!(saved.contains("\ntail true\n"), "{saved}");
+
+        let evaluation = formula
+            .evaluate_checkpointed::<Sum<3>>(2_000, 1, 3, &checkpoint)
+            .unwrap();
+        assert_eq!(evaluation.value.digits, expected.value.digits);
+        assert_eq!(evaluation.error, expected.error);
+        fs::remove_file(checkpoint.path()).unwrap();
+    }
+
+    #[test]
+    fn other_computations_are_rejected() {
+        let formula = formulas::bellards_pi();
+        let checkpoint = checkpoint("mismatch");
+        let partial = formula.evaluate_range::<Sum<3>>(500, 0..10, 3).unwrap();
+        formula.save_checkpoint(&partial, &checkpoint).unwrap();
+
+        let mismatch = |formula: &PolyFormula<1, 2>, digit, words| {
+            let err = match words {
+                3 => formula
+                    .evaluate_checkpointed::<Sum<3>>(digit, 1, words, &checkpoint)
+                    .unwrap_err(),
+                _ => formula
+                    .evaluate_checkpointed::<Sum<4>>(digit, 1, words, &checkpoint)
+                    .unwrap_err(),
+            };
+            match err {
+                CheckpointError::Mismatch { field, .. } => field,
+                err => panic!("{err}"),
+            }
+        };
+        assert_eq!(
+            mismatch(&formulas::bailey_borwein_plouffe_pi(), 500, 3),
+            "formula"
+        );
+        assert_eq!(
+            mismatch(&formula.clone().with_fusion(true), 500, 3),
+            "fusion"
+        );
+        assert_eq!(mismatch(&formula, 501, 3), "digit");
+        assert_eq!(mismatch(&formula, 500, 4), "words");
+        fs::remove_file(checkpoint.path()).unwrap();
+    }
+
+    #[test]
+    fn malformed_checkpoints_are_rejected() {
+        let formula = formulas::bellards_pi();
+        let partial = formula.evaluate_range::<Sum<3>>(500, 0..10, 3).unwrap();
+        let checkpoint = checkpoint("malformed");
+        formula.save_checkpoint(&partial, &checkpoint).unwrap();
+        let saved = fs::read_to_string(checkpoint.path()).unwrap();
+        fs::remove_file(checkpoint.path()).unwrap();
+        assert!(formula.parse_checkpoint::<Sum<3>>(&saved, 500, 3).is_ok());
+
+        let ranges = |ranges: &str| saved.replace("ranges 0..10", ranges);
+        for malformed in [
+            String::new(),
+            saved.replace("droplet checkpoint 1", "droplet checkpoint 0"),
+            saved.replace("tail false", "tail maybe"),
+            saved.replace("divisions ", "divisions -"),
+            saved.replace("\nvalue ", "\nvalue x"),
+            ranges("ranges 0-10"),
+            ranges("ranges 5..5"),
+            ranges("ranges 10..0"),
+            ranges("ranges 0..502"),
+            ranges("ranges 20..30 0..10"),
+            ranges("ranges 0..10 5..20"),
+            ranges("ranges 0..10 10..20"),
+        ] {
+            let err = formula
+                .parse_checkpoint::<Sum<3>>(&malformed, 500, 3)
+                .unwrap_err();
+            assert!(
+                matches!(err, CheckpointError::Malformed(_)),
+                "{malformed:?}: {err}"
+            );
+        }
+        assert!(
+            formula
+                .parse_checkpoint::<Sum<3>>(&ranges("ranges 0..10 11..501"), 500, 3)
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use crate::{
+    Checked, Evaluation, PolyFormula,
+    checkpoint::{Checkpoint, CheckpointError},
+    progress::{Cancelled, Monitor},
+    tune::Tuning,
+    wide::{DynSum, Fraction, Sum},
+};
+
+/// Digits returned by [`digits`], every one of them provably correct.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Digits {
+    /// Position of the first digit after the radix point, counting from zero.
+    pub position: u64,
+    pub radix: u32,
+    /// Digit values, most significant first.
+    pub values: Vec<u8>,
+}
+
+impl std::fmt::Display for Digits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for &value in &self.values {
+            let digit = char::from_digit(value.into(), self.radix).ok_or(std::fmt::Error)?;
+            write!(f, "{digit}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DigitsError {
+    /// Only powers of two from 2 to 32 are supported, as digits in other
+    /// bases cannot be extracted at an arbitrary position.
+    UnsupportedRadix(u32),
+    /// The position is past the last digit the formula can be evaluated at.
+    PositionTooLarge(u64),
+    /// The digits span more bits than an evaluation can check.
+    CountTooLarge(u32),
+    /// The exact value sits so close to a digit boundary that even the retry
+    /// could not settle every digit; only the first `certain` are known.
+    Ambiguous {
+        certain: u32,
+    },
+    Checkpoint(CheckpointError),
+    /// An evaluation failed with an [`std::io::Error`] of this kind and
+    /// message.
+    Io {
+        kind: std::io::ErrorKind,
+        message: String,
+    },
+    Cancelled,
+    /// Two overlapping windows of a [`crate::stream::DigitStream`] disagree
+    /// on the digit at `position`.
+    Inconsistent {
+        position: u64,
+    },
+}
+
+impl std::fmt::Display for DigitsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DigitsError::UnsupportedRadix(radix) => {
+                write!(
+                    f,
+                    "unsupported radix {radix}, expected a power of two from 2 to 32"
+                )
+            }
+            DigitsError::PositionTooLarge(position) => {
+                write!(f, "position {position} is too large")
+            }
+            DigitsError::CountTooLarge(count) => write!(f, "{count} digits are too many"),
+            DigitsError::Ambiguous { certain } => {
+                write!(f, "digits are ambiguous, only {certain} are certain")
+            }
+            DigitsError::Checkpoint(err) => write!(f, "{err}"),
+            DigitsError::Io { message, .. } => write!(f, "{message}"),
+            DigitsError::Cancelled => write!(f, "{Cancelled}"),
+            DigitsError::Inconsistent { position } => {
+                write!(f, "overlapping windows disagree on digit {position}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DigitsError {}
+
+impl From<Cancelled> for DigitsError {
+    fn from(_: Cancelled) -> Self {
+        DigitsError::Cancelled
+    }
+}
+
+impl From<std::io::Error> for DigitsError {
+    fn from(err: std::io::Error) -> Self {
+        DigitsError::Io {
+            kind: err.kind(),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<CheckpointError> for DigitsError {
+    fn from(err: CheckpointError) -> Self {
+        DigitsError::Checkpoint(err)
+    }
+}
+
+/// Where the requested digits sit in the formula's output, and the width
+/// needed to settle them.
+struct Plan {
+    /// Digit in the formula's base containing the first requested bit.
+    digit: u32,
+    /// Bits of that digit before the first requested bit.
+    skip: u32,
+    /// Fraction bits that must be certain.
+    bits: u32,
+    digit_bits: u32,
+    words: usize,
+}
+
+impl Plan {
+    fn new<const N_DEGREE: usize, const D_DEGREE: usize>(
+        formula: &PolyFormula<N_DEGREE, D_DEGREE>,
+        position: u64,
+        count: u32,
+        radix: u32,
+    ) -> Result<Self, DigitsError> {
+        if !radix.is_power_of_two() || !(2..=32).contains(&radix) {
+            return Err(DigitsError::UnsupportedRadix(radix));
+        }
+        let digit_bits = radix.trailing_zeros();
+
+        // The formula yields digits in base 2^b, so start at the one
+        // containing the first requested bit and skip the bits before it
+        let first_bit = position
+            .checked_mul(digit_bits.into())
+            .ok_or(DigitsError::PositionTooLarge(position))?;
+        let base_log2 = u64::from(formula.base_log2);
+        let digit = u32::try_from(first_bit / base_log2)
+            .map_err(|_| DigitsError::PositionTooLarge(position))?;
+        let skip = (first_bit % base_log2) as u32;
+        let bits = count
+            .checked_mul(digit_bits)
+            .and_then(|bits| bits.checked_add(skip))
+            .ok_or(DigitsError::CountTooLarge(count))?;
+
+        // The integer word, the requested bits, and one word to absorb the
+        // error
+        let words = 2 + bits.div_ceil(u64::BITS) as usize;
+        Ok(Self {
+            digit,
+            skip,
+            bits,
+            digit_bits,
+            words,
+        })
+    }
+
+    /// Slices the requested digits out of the fraction words, or reports
+    /// how many are certain given the number of certain bits.
+    fn extract(
+        &self,
+        fraction: Result<Vec<u64>, u32>,
+        position: u64,
+        count: u32,
+    ) -> Result<Digits, DigitsError> {
+        let fraction = fraction.map_err(|correct_bits| DigitsError::Ambiguous {
+            certain: correct_bits.saturating_sub(self.skip) / self.digit_bits,
+        })?;
+        let values = (0..count)
+            .map(|idx| {
+                bits_at(
+                    &fraction,
+                    self.skip + idx * self.digit_bits,
+                    self.digit_bits,
+                )
+            })
+            .collect();
+        Ok(Digits {
+            position,
+            radix: 1 << self.digit_bits,
+            values,
+        })
+    }
+}
+
+fn threads() -> usize {
+    Tuning::current().threads
+}
+
+/// Computes `count` digits in base `radix` of the constant given by
+/// `formula`, starting `position` digits after the radix point.
+///
+/// The precision is planned from the position and count, falling back to a
+/// wider retry when the first evaluation cannot settle every digit, and the
+/// evaluation runs on the threads given by [`Tuning::current`].
+pub fn digits<const N_DEGREE: usize, const D_DEGREE: usize>(
+    formula: impl Into<PolyFormula<N_DEGREE, D_DEGREE>>,
+    position: u64,
+    count: u32,
+    radix: u32,
+) -> Result<Digits, DigitsError> {
+    digits_monitored(formula, position, count, radix, &Monitor::new())
+}
+
+/// [`digits`], reporting the progress of each evaluation to `monitor` and
+/// stopping early if its token is cancelled.
+pub fn digits_monitored<const N_DEGREE: usize, const D_DEGREE: usize>(
+    formula: impl Into<PolyFormula<N_DEGREE, D_DEGREE>>,
+    position: u64,
+    count: u32,
+    radix: u32,
+    monitor: &Monitor,
+) -> Result<Digits, DigitsError> {
+    let formula = formula.into();
+    let plan = Plan::new(&formula, position, count, radix)?;
+    let (digit, bits, threads) = (plan.digit, plan.bits, threads());
+    let fraction = match plan.words {
+        ..=3 => check::<Sum<3>, Sum<5>, _>(
+            bits,
+            || formula.evaluate_monitored(digit, threads, 3, monitor),
+            || formula.evaluate_monitored(digit, threads, 5, monitor),
+        )?,
+        4 => check::<Sum<4>, Sum<6>, _>(
+            bits,
+            || formula.evaluate_monitored(digit, threads, 4, monitor),
+            || formula.evaluate_monitored(digit, threads, 6, monitor),
+        )?,
+        words => check::<DynSum, DynSum, _>(
+            bits,
+            || formula.evaluate_monitored(digit, threads, words, monitor),
+            || formula.evaluate_monitored(digit, threads, words + 2, monitor),
+        )?,
+    };
+    plan.extract(fraction, position, count)
+}
+
+/// [`digits`] with each evaluation made by `evaluate`, which is given the
+/// formula, the digit to evaluate at, the number of words, and whether it is
+/// the retry.
+pub fn digits_with<const N_DEGREE: usize, const D_DEGREE: usize, E: Into<DigitsError>>(
+    formula: impl Into<PolyFormula<N_DEGREE, D_DEGREE>>,
+    position: u64,
+    count: u32,
+    radix: u32,
+    evaluate: impl Fn(
+        &PolyFormula<N_DEGREE, D_DEGREE>,
+        u32,
+        usize,
+        bool,
+    ) -> Result<Evaluation<DynSum>, E>,
+) -> Result<Digits, DigitsError> {
+    let formula = formula.into();
+    let plan = Plan::new(&formula, position, count, radix)?;
+    let (digit, bits, words) = (plan.digit, plan.bits, plan.words);
+    let fraction = check(
+        bits,
+        || evaluate(&formula, digit, words, false),
+        || evaluate(&formula, digit, words + 2, true),
+    )
+    .map_err(Into::into)?;
+    plan.extract(fraction, position, count)
+}
+
+/// [`digits`], saving progress to `checkpoint` and resuming from it. A
+/// retry with more words saves to the same path with `.retry` appended.
+/// Checkpoint files are left in place once finished, so asking again for
+/// the same digits returns at once.
+pub fn digits_checkpointed<const N_DEGREE: usize, const D_DEGREE: usize>(
+    formula: impl Into<PolyFormula<N_DEGREE, D_DEGREE>>,
+    position: u64,
+    count: u32,
+    radix: u32,
+    checkpoint: &Checkpoint,
+) -> Result<Digits, DigitsError> {
+    let threads = threads();
+    digits_with(
+        formula,
+        position,
+        count,
+        radix,
+        |formula, digit, words, retry| {
+            let checkpoint = match retry {
+                false => checkpoint.clone(),
+                true => checkpoint.with_suffix(".retry"),
+            };
+            formula.evaluate_checkpointed(digit, threads, words, &checkpoint)
+        },
+    )
+}
+
+/// Runs [`PolyFormula::autotune`] at the digit and width [`digits`]
+/// first evaluates at for the same arguments.
+pub fn autotune<const N_DEGREE: usize, const D_DEGREE: usize>(
+    formula: impl Into<PolyFormula<N_DEGREE, D_DEGREE>>,
+    position: u64,
+    count: u32,
+    radix: u32,
+) -> Result<Tuning, DigitsError> {
+    let formula = formula.into();
+    let plan = Plan::new(&formula, position, count, radix)?;
+    Ok(match plan.words {
+        ..=3 => formula.autotune::<Sum<3>>(plan.digit, 3),
+        4 => formula.autotune::<Sum<4>>(plan.digit, 4),
+        words => formula.autotune::<DynSum>(plan.digit, words),
+    })
+}
+
+/// Evaluates with `first`, and again with `retry` if the first `bits` are
+/// not all certain. Returns the fraction words if they are certain in the
+/// end, or else the number of bits that are.
+fn check<S: Fraction, R: Fraction, E>(
+    bits: u32,
+    first: impl FnOnce() -> Result<Evaluation<S>, E>,
+    retry: impl FnOnce() -> Result<Evaluation<R>, E>,
+) -> Result<Result<Vec<u64>, u32>, E> {
+    let checked = Checked::resolve(bits, first, retry)?;
+    Ok(match checked {
+        Checked::Ambiguous(_) => Err(checked.correct_bits().min(bits)),
+        _ => Ok(checked.fraction().to_vec()),
+    })
+}
+
+/// Reads `width` bits of `fraction` starting `start` bits after the radix
+/// point.
+fn bits_at(fraction: &[u64], start: u32, width: u32) -> u8 {
+    let idx = (start / u64::BITS) as usize;
+    let high = u128::from(fraction[idx]) << 64;
+    let low = u128::from(fraction.get(idx + 1).copied().unwrap_or(0));
+    (((high | low) << (start % u64::BITS)) >> (u128::BITS - width)) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formulas::Constant;
+
+    #[test]
+    fn too_narrow_evaluations_retry_or_report_ambiguity() {
+        let expected = digits(Constant::Pi, 1_000, 20, 16).unwrap();
+        // The first evaluation has one fraction word for 80 bits
+        let narrow = |words| {
+            move |formula: &PolyFormula<1, 2>, digit, _, retry| {
+                let words = if retry { words } else { 2 };
+                Ok::<_, DigitsError>(formula.evaluate_bounded::<DynSum>(digit, 1, words))
+            }
+        };
+        assert_eq!(
+            digits_with(Constant::Pi, 1_000, 20, 16, narrow(4)),
+            Ok(expected)
+        );
+        let Err(DigitsError::Ambiguous { certain }) =
+            digits_with(Constant::Pi, 1_000, 20, 16, narrow(2))
+        else {
+            panic!("a one-word retry cannot settle 20 digits");
+        };
+        assert!((1..16).contains(&certain), "{certain} certain");
+    }
+}
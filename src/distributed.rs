@@ -0,0 +1,474 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    ops::Range,
+    sync::{Condvar, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    Evaluation, PolyFormula,
+    partial::Partial,
+    wide::{DynSum, Fraction},
+};
+
+/// Largest frame either side accepts, well above any realistic width.
+const MAX_FRAME: u32 = 1 << 24;
+
+/// How often the coordinator checks for new workers while evaluating.
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+
+/// Messages exchanged between the coordinator and its workers, each sent as
+/// a frame holding its length, a tag byte and big-endian fields.
+#[derive(Debug, PartialEq, Eq)]
+enum Message {
+    /// Coordinator to worker on connecting and at the start of every later
+    /// evaluation: the evaluation to take part in.
+    Job {
+        identity: String,
+        fusion: bool,
+        digit: u32,
+        words: u32,
+    },
+    /// Worker to coordinator: the job matches the worker's formula.
+    Ready,
+    /// Coordinator to worker: sum the indices in `start..end`.
+    Assign { start: u32, end: u32 },
+    /// Worker to coordinator: the sum over `start..end`.
+    Sum {
+        start: u32,
+        end: u32,
+        value: Vec<u64>,
+    },
+    /// Coordinator to worker: every range has been summed, so wait for the
+    /// next job.
+    Done,
+}
+
+impl Message {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        match self {
+            Message::Job {
+                identity,
+                fusion,
+                digit,
+                words,
+            } => {
+                bytes.push(0);
+                bytes.extend((identity.len() as u32).to_be_bytes());
+                bytes.extend(identity.as_bytes());
+                bytes.push(u8::from(*fusion));
+                bytes.extend(digit.to_be_bytes());
+                bytes.extend(words.to_be_bytes());
+            }
+            Message::Ready => bytes.push(1),
+            Message::Assign { start, end } => {
+                bytes.push(2);
+                bytes.extend(start.to_be_bytes());
+                bytes.extend(end.to_be_bytes());
+            }
+            Message::Sum { start, end, value } => {
+                bytes.push(3);
+                bytes.extend(start.to_be_bytes());
+                bytes.extend(end.to_be_bytes());
+                bytes.extend((value.len() as u32).to_be_bytes());
+                for word in value {
+                    bytes.extend(word.to_be_bytes());
+                }
+            }
+            Message::Done => bytes.push(4),
+        }
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> io::Result<Self> {
+        let mut reader = bytes;
+        let message = match take::<1>(&mut reader)? {
+            [0] => {
+                let length = u32::from_be_bytes(take(&mut reader)?) as usize;
+                if reader.len() < length {
+                    return Err(invalid("truncated identity"));
+                }
+                let (identity, rest) = reader.split_at(length);
+                reader = rest;
+                let identity = String::from_utf8(identity.to_vec())
+                    .map_err(|_| invalid("identity is not UTF-8"))?;
+                Message::Job {
+                    identity,
+                    fusion: take::<1>(&mut reader)? != [0],
+                    digit: u32::from_be_bytes(take(&mut reader)?),
+                    words: u32::from_be_bytes(take(&mut reader)?),
+                }
+            }
+            [1] => Message::Ready,
+            [2] => Message::Assign {
+                start: u32::from_be_bytes(take(&mut reader)?),
+                end: u32::from_be_bytes(take(&mut reader)?),
+            },
+            [3] => {
+                let start = u32::from_be_bytes(take(&mut reader)?);
+                let end = u32::from_be_bytes(take(&mut reader)?);
+                let length = u32::from_be_bytes(take(&mut reader)?);
+                let value = (0..length)
+                    .map(|_| Ok(u64::from_be_bytes(take(&mut reader)?)))
+                    .collect::<io::Result<_>>()?;
+                Message::Sum { start, end, value }
+            }
+            [4] => Message::Done,
+            [tag] => return Err(invalid(&format!("unknown message tag {tag}"))),
+        };
+        if !reader.is_empty() {
+            return Err(invalid("trailing bytes in frame"));
+        }
+        Ok(message)
+    }
+
+    fn send(&self, stream: &mut impl Write) -> io::Result<()> {
+        let bytes = self.encode();
+        stream.write_all(&(bytes.len() as u32).to_be_bytes())?;
+        stream.write_all(&bytes)?;
+        stream.flush()
+    }
+
+    fn receive(stream: &mut impl Read) -> io::Result<Self> {
+        let mut length = [0; 4];
+        stream.read_exact(&mut length)?;
+        let length = u32::from_be_bytes(length);
+        if length > MAX_FRAME {
+            return Err(invalid(&format!("frame of {length} bytes is too long")));
+        }
+        let mut bytes = vec![0; length as usize];
+        stream.read_exact(&mut bytes)?;
+        Self::decode(&bytes)
+    }
+}
+
+/// Splits the first `N` bytes off `reader`.
+fn take<const N: usize>(reader: &mut &[u8]) -> io::Result<[u8; N]> {
+    let Some((bytes, rest)) = reader.split_first_chunk() else {
+        return Err(invalid("truncated frame"));
+    };
+    *reader = rest;
+    Ok(*bytes)
+}
+
+fn invalid(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+/// Listens for workers and hands out index ranges to them in
+/// [`PolyFormula::evaluate_distributed`]. Workers stay connected between
+/// evaluations and take part in every later one.
+#[derive(Debug)]
+pub struct Coordinator {
+    listener: TcpListener,
+    chunk: u32,
+    timeout: Duration,
+    /// Workers that finished the last evaluation, waiting for the next job.
+    idle: Mutex<Vec<TcpStream>>,
+}
+
+impl Coordinator {
+    /// Listens on `address`, handing out ranges of 2^20 indices and giving
+    /// up on a worker that takes more than ten minutes to sum one.
+    pub fn bind(address: impl ToSocketAddrs) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(address)?,
+            chunk: 1 << 20,
+            timeout: Duration::from_secs(600),
+            idle: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub fn with_chunk(mut self, chunk: u32) -> Self {
+        self.chunk = chunk.max(1);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn local_addr(&self) -> io::Result<std::net::SocketAddr> {
+        self.listener.local_addr()
+    }
+}
+
+/// Ranges waiting for a worker, the number being summed, and the sum of the
+/// ranges returned so far.
+struct Progress<S> {
+    pending: VecDeque<Range<u32>>,
+    assigned: usize,
+    partial: Partial<S>,
+}
+
+impl<S> Progress<S> {
+    fn finished(&self) -> bool {
+        self.pending.is_empty() && self.assigned == 0
+    }
+}
+
+impl<const N_DEGREE: usize, const D_DEGREE: usize> PolyFormula<N_DEGREE, D_DEGREE> {
+    /// Evaluates at `digit` to `words` words like
+    /// [`PolyFormula::evaluate_bounded`], with the indices up to
+    /// `digit` summed by workers connected to `coordinator`. A range is handed
+    /// to another worker if the one summing it disconnects or times out.
+    /// Returns once every range has been summed, however many workers come
+    /// and go in the meantime.
+    pub fn evaluate_distributed<S: Fraction>(
+        &self,
+        digit: u32,
+        words: usize,
+        coordinator: &Coordinator,
+    ) -> io::Result<Evaluation<S>> {
+        let end = digit + 1;
+        let pending = (0..end)
+            .step_by(coordinator.chunk as usize)
+            .map(|start| start..start.saturating_add(coordinator.chunk).min(end))
+            .collect();
+        let progress = Mutex::new(Progress {
+            pending,
+            assigned: 0,
+            partial: self.evaluate_tail(digit, words),
+        });
+        let changed = Condvar::new();
+
+        coordinator.listener.set_nonblocking(true)?;
+        thread::scope(|scope| -> io::Result<()> {
+            let (progress, changed) = (&progress, &changed);
+            let spawn = |stream| {
+                scope.spawn(move || {
+                    self.coordinate_worker(stream, digit, words, coordinator, progress, changed)
+                });
+            };
+            let idle = std::mem::take(&mut *coordinator.idle.lock().unwrap());
+            for stream in idle {
+                spawn(stream);
+            }
+            while !progress.lock().unwrap().finished() {
+                match coordinator.listener.accept() {
+                    Ok((stream, _)) => spawn(stream),
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                        thread::sleep(ACCEPT_POLL);
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            Ok(())
+        })?;
+
+        let partial = progress.into_inner().unwrap().partial;
+        partial
+            .into_evaluation()
+            .ok_or_else(|| invalid("summed ranges do not cover every index"))
+    }
+
+    /// Hands ranges to the worker on `stream` until none are left, putting
+    /// back the one it was summing if it fails. A worker that sees the
+    /// evaluation through is kept for the next one.
+    fn coordinate_worker<S: Fraction>(
+        &self,
+        mut stream: TcpStream,
+        digit: u32,
+        words: usize,
+        coordinator: &Coordinator,
+        progress: &Mutex<Progress<S>>,
+        changed: &Condvar,
+    ) {
+        let handshake = (|| {
+            stream.set_nonblocking(false)?;
+            stream.set_read_timeout(Some(coordinator.timeout))?;
+            Message::Job {
+                identity: self.identity(),
+                fusion: self.fusion,
+                digit,
+                words: words as u32,
+            }
+            .send(&mut stream)?;
+            match Message::receive(&mut stream)? {
+                Message::Ready => Ok(()),
+                message => Err(invalid(&format!("expected ready, got {message:?}"))),
+            }
+        })();
+        if handshake.is_err() {
+            return;
+        }
+
+        loop {
+            let range = {
+                let mut progress = progress.lock().unwrap();
+                loop {
+                    if let Some(range) = progress.pending.pop_front() {
+                        progress.assigned += 1;
+                        break range;
+                    }
+                    if progress.finished() {
+                        if Message::Done.send(&mut stream).is_ok() {
+                            coordinator.idle.lock().unwrap().push(stream);
+                        }
+                        return;
+                    }
+                    progress = changed.wait(progress).unwrap();
+                }
+            };
+
+            let summed = self.assign(&mut stream, range.clone(), digit, words);
+            let mut progress = progress.lock().unwrap();
+            progress.assigned -= 1;
+            changed.notify_all();
+            match summed {
+                Ok(chunk) => progress
+                    .partial
+                    .merge_mut(chunk)
+                    .expect("ranges are disjoint"),
+                Err(_) => {
+                    progress.pending.push_back(range);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Has the worker on `stream` sum `range` and checks its reply.
+    fn assign<S: Fraction>(
+        &self,
+        stream: &mut TcpStream,
+        range: Range<u32>,
+        digit: u32,
+        words: usize,
+    ) -> io::Result<Partial<S>> {
+        Message::Assign {
+            start: range.start,
+            end: range.end,
+        }
+        .send(stream)?;
+        match Message::receive(stream)? {
+            Message::Sum { start, end, value }
+                if start == range.start && end == range.end && value.len() == words =>
+            {
+                let mut sum = S::zero(words);
+                sum.digits_mut().copy_from_slice(&value);
+                Ok(Partial {
+                    digit,
+                    value: sum,
+                    ranges: vec![range.clone()],
+                    tail: None,
+                    divisions: range.len() as u64 * self.numerators.len() as u64,
+                })
+            }
+            message => Err(invalid(&format!(
+                "expected the sum over {range:?}, got {message:?}"
+            ))),
+        }
+    }
+
+    /// Connects to the coordinator at `address` and sums the ranges it
+    /// hands out, evaluation after evaluation, until it disconnects. Fails
+    /// without summing anything if the coordinator is evaluating a different
+    /// formula.
+    pub fn work_for(&self, address: impl ToSocketAddrs) -> io::Result<()> {
+        let mut stream = TcpStream::connect(address)?;
+        loop {
+            let message = match Message::receive(&mut stream) {
+                Ok(message) => message,
+                // The coordinator hung up between evaluations
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(err) => return Err(err),
+            };
+            let (digit, words) = match message {
+                Message::Job {
+                    identity,
+                    fusion,
+                    digit,
+                    words,
+                } => {
+                    if identity != self.identity() || fusion != self.fusion {
+                        return Err(invalid(&format!(
+                            "coordinator is evaluating {identity} with fusion {fusion}"
+                        )));
+                    }
+                    (digit, words as usize)
+                }
+                message => return Err(invalid(&format!("expected a job, got {message:?}"))),
+            };
+            Message::Ready.send(&mut stream)?;
+            self.sum_assigned(&mut stream, digit, words)?;
+        }
+    }
+
+    /// Sums the ranges assigned on `stream` for one evaluation, until the
+    /// coordinator says it is done.
+    fn sum_assigned(&self, stream: &mut TcpStream, digit: u32, words: usize) -> io::Result<()> {
+        loop {
+            match Message::receive(stream)? {
+                Message::Assign { start, end } => {
+                    let partial: Partial<DynSum> = self
+                        .evaluate_range(digit, start..end, words)
+                        .map_err(|err| invalid(&err.to_string()))?;
+                    Message::Sum {
+                        start,
+                        end,
+                        value: partial.value.digits,
+                    }
+                    .send(stream)?;
+                }
+                Message::Done => return Ok(()),
+                message => return Err(invalid(&format!("unexpected {message:?}"))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::formulas;
+
+    #[test]
+    fn ranges_from_dropped_workers_are_reassigned() {
+        let formula = formulas::bellards_pi();
+        let expected: Evaluation<DynSum> = formula.evaluate_bounded(3_000, 1, 3);
+        for partial_reply in [false, true] {
+            let coordinator = Coordinator::bind("127.0.0.1:0").unwrap().with_chunk(500);
+            let address = coordinator.local_addr().unwrap();
+            let (assigned, started) = mpsc::channel();
+
+            // Hangs up partway through its first range, before any other
+            // worker connects
+            let dropped = thread::spawn(move || {
+                let mut stream = TcpStream::connect(address).unwrap();
+                let job = Message::receive(&mut stream).unwrap();
+                assert!(matches!(job, Message::Job { .. }), "{job:?}");
+                Message::Ready.send(&mut stream).unwrap();
+                let assign = Message::receive(&mut stream).unwrap();
+                assert!(matches!(assign, Message::Assign { .. }), "{assign:?}");
+                if partial_reply {
+                    stream.write_all(&[0, 0, 0, 40, 3]).unwrap();
+                }
+                assigned.send(()).unwrap();
+            });
+            let worker = {
+                let formula = formula.clone();
+                thread::spawn(move || {
+                    started.recv().unwrap();
+                    formula.work_for(address)
+                })
+            };
+
+            let evaluation = formula
+                .evaluate_distributed::<DynSum>(3_000, 3, &coordinator)
+                .unwrap();
+            assert_eq!(evaluation.value.digits, expected.value.digits);
+            assert_eq!(evaluation.error, expected.error);
+            dropped.join().unwrap();
+            drop(coordinator);
+            worker.join().unwrap().unwrap();
+        }
+    }
+}
@@ -64,3 +64,18 @@ pub fn zero() -> PolyFormula<1, 2> {
     let zero_denominators = (1..=5).map(|j| Polynomial::new([j, 6])).collect();
     PolyFormula::new(false, 6, zero_numerators, zero_denominators)
 }
+
+/// Constants with a built-in formula, accepted wherever a formula is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Constant {
+    /// Pi, with [`bellards_pi`], the fastest of the formulas for it.
+    Pi,
+}
+
+impl From<Constant> for PolyFormula<1, 2> {
+    fn from(constant: Constant) -> Self {
+        match constant {
+            Constant::Pi => bellards_pi(),
+        }
+    }
+}
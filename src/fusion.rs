@@ -0,0 +1,290 @@
+use crate::{
+    Index, PolyFormula,
+    modular::Modulus128,
+    wide::{Accumulator, Fraction},
+};
+
+/// Largest number of terms fused over one common denominator.
+const MAX_FUSED: usize = 8;
+
+/// Number of consecutive indices that share one fusion plan, a power of two.
+const PLAN_BLOCK: u32 = 4096;
+
+/// Costs, in nanoseconds, of one exponentiation step (per exponent bit) and
+/// one long division step (per `Sum` word), for 64-bit and 128-bit moduli.
+/// Measured by `examples/fusion_costs.rs` in a release build on an x86-64
+/// machine; only their ratios matter to the plan, and those vary less
+/// between machines than the times themselves.
+const POW_BIT_COST: [f64; 2] = [13.0, 15.0];
+const DIV_WORD_COST: [f64; 2] = [11.5, 21.0];
+
+impl<const N_DEGREE: usize, const D_DEGREE: usize> PolyFormula<N_DEGREE, D_DEGREE> {
+    /// Estimates the cost of evaluating one group of terms over the product
+    /// of their denominators to `words` words, given that product's bit
+    /// length.
+    fn group_cost(&self, product_bits: u32, digit: u32, i: u32, words: usize) -> f64 {
+        let width = usize::from(product_bits > 64);
+        let exponent = u64::from(self.base_log2) * u64::from(digit.saturating_sub(i)).max(1);
+        let exponent_bits = f64::from(u64::BITS - exponent.leading_zeros());
+        exponent_bits * POW_BIT_COST[width] + words as f64 * DIV_WORD_COST[width]
+    }
+
+    /// Partitions the terms into groups evaluated over a common denominator
+    /// at indices up to `i`, merging groups while the product of their
+    /// denominators fits in 128 bits and the estimated cost goes down.
+    pub fn fusion_groups(&self, digit: u32, i: u32, words: usize) -> Vec<Vec<usize>> {
+        let bits = |group: &[usize]| -> Option<u32> {
+            let product = group.iter().try_fold(1u128, |product, &term| {
+                product.checked_mul(self.odd_denominator(term, i).0.into())
+            })?;
+            Some(u128::BITS - product.leading_zeros())
+        };
+        let cost = |group: &[usize]| -> Option<f64> {
+            Some(self.group_cost(bits(group)?, digit, i, words))
+        };
+
+        let mut groups: Vec<Vec<usize>> = (0..self.numerators.len()).map(|t| vec![t]).collect();
+        loop {
+            let mut best = None;
+            for a in 0..groups.len() {
+                for b in (a + 1)..groups.len() {
+                    if groups[a].len() + groups[b].len() > MAX_FUSED {
+                        continue;
+                    }
+                    let merged = [groups[a].as_slice(), groups[b].as_slice()].concat();
+                    let (Some(separate_a), Some(separate_b), Some(fused)) =
+                        (cost(&groups[a]), cost(&groups[b]), cost(&merged))
+                    else {
+                        continue;
+                    };
+                    let saving = separate_a + separate_b - fused;
+                    if saving > 0.0 && best.is_none_or(|(_, _, best_saving)| saving > best_saving) {
+                        best = Some((a, b, saving));
+                    }
+                }
+            }
+            let Some((a, b, _)) = best else {
+                return groups;
+            };
+            let merged = groups.swap_remove(b);
+            groups[a].extend(merged);
+        }
+    }
+
+    /// Computes the fractional part of the sum of `group` at `index` with a
+    /// single exponentiation modulo the product of the denominators. Returns
+    /// `None` when that product overflows 128 bits or the index is too close
+    /// to `digit` for the shared power of two to be whole.
+    fn evaluate_fused<S: Fraction>(
+        &self,
+        group: &[usize],
+        index: Index,
+        words: usize,
+    ) -> Option<S> {
+        let mut parts = [(false, 0, 1, 0); MAX_FUSED];
+        for (part, &term) in parts.iter_mut().zip(group) {
+            *part = self.term_parts(term, index);
+        }
+        let parts = &parts[..group.len()];
+
+        let scale = parts.iter().map(|&(_, _, _, scale)| scale).max()?;
+        let (exponent, shift) = self.split_exponent(index, scale).ok()?;
+        let product = parts
+            .iter()
+            .try_fold(1u128, |product, &(_, _, denominator, _)| {
+                product.checked_mul(denominator.into())
+            })?;
+
+        // Bring every numerator over the common denominator modulo the product
+        let mut numerator = 0u128;
+        for (idx, &(positive, term_numerator, _, term_scale)) in parts.iter().enumerate() {
+            let cofactor = parts
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != idx)
+                .fold(1u128, |cofactor, (_, &(_, _, denominator, _))| {
+                    cofactor * u128::from(denominator)
+                });
+            let scaled =
+                u128::from(term_numerator).checked_mul(1u128.checked_shl(scale - term_scale)?)?;
+            let mut value = scaled.checked_mul(cofactor)?;
+            if value >= product {
+                value %= product;
+            }
+            numerator = if positive {
+                let complement = product - numerator;
+                if value >= complement {
+                    value - complement
+                } else {
+                    numerator + value
+                }
+            } else if numerator >= value {
+                numerator - value
+            } else {
+                product - (value - numerator)
+            };
+        }
+
+        match u64::try_from(product) {
+            Ok(product) => Some(self.fraction(numerator as u64, product, scale, index, words)),
+            Err(_) => {
+                let modulus = Modulus128::new(product);
+                let exponent = exponent * u64::from(self.base_log2) + u64::from(shift);
+                let residue = modulus.mod_pow2_init(numerator, exponent);
+                Some(S::from_ratio(words, residue, &modulus))
+            }
+        }
+    }
+
+    /// Evaluates every term at each index in `indices`, fusing terms into
+    /// groups chosen by [`PolyFormula::fusion_groups`] once per block. Blocks
+    /// are aligned to multiples of [`PLAN_BLOCK`], so every index is fused the
+    /// same way however the indices are split up.
+    pub(crate) fn evaluate_fused_indices<S: Fraction>(
+        &self,
+        digit: u32,
+        indices: std::ops::RangeInclusive<u32>,
+        words: usize,
+    ) -> S {
+        let (first, last) = (*indices.start(), *indices.end());
+        let mut sum = Accumulator::zero(words);
+        let mut start = first;
+        while start <= last {
+            let block_end = start | (PLAN_BLOCK - 1);
+            let end = block_end.min(last);
+            // Denominators grow with the index, so plan for the largest
+            let groups = self.fusion_groups(digit, block_end, words);
+            let singles: Vec<usize> = groups
+                .iter()
+                .filter(|group| group.len() == 1)
+                .map(|group| group[0])
+                .collect();
+            let pairs = (start..=end).flat_map(|i| {
+                let index = self.index(digit, i);
+                singles.iter().map(move |&term| (term, index))
+            });
+            sum += self.evaluate_pairs::<S>(pairs, words);
+            for i in start..=end {
+                let index = self.index(digit, i);
+                for group in groups.iter().filter(|group| group.len() > 1) {
+                    sum = match self.evaluate_fused::<S>(group, index, words) {
+                        Some(value) => Self::accumulate(sum, (true, value)),
+                        None => group.iter().fold(sum, |sum, &term| {
+                            Self::accumulate(sum, self.evaluate_index::<S>(term, index, words))
+                        }),
+                    };
+                }
+            }
+            let Some(next) = end.checked_add(1) else {
+                break;
+            };
+            start = next;
+        }
+        sum.sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        formulas,
+        wide::{DynSum, Sum},
+    };
+
+    #[test]
+    fn fusion_groups_partition_the_terms() {
+        let formula = formulas::bellards_pi();
+        for (digit, i) in [(1_000, 0), (1_000, 1_000), (100_000, 50_000), (10, 4_095)] {
+            let groups = formula.fusion_groups(digit, i, 4);
+            let mut terms: Vec<usize> = groups.iter().flatten().copied().collect();
+            terms.sort_unstable();
+            assert_eq!(terms, (0..7).collect::<Vec<_>>(), "index {i}");
+            for group in &groups {
+                assert!(group.len() <= MAX_FUSED);
+                let product = group.iter().try_fold(1u128, |product, &term| {
+                    product.checked_mul(formula.odd_denominator(term, i).0.into())
+                });
+                assert!(product.is_some(), "group {group:?} at index {i}");
+            }
+        }
+        assert!(
+            formula
+                .fusion_groups(100_000, 50_000, 4)
+                .iter()
+                .any(|group| group.len() > 1)
+        );
+    }
+
+    #[test]
+    fn fused_group_matches_its_terms() {
+        let formula = formulas::bellards_pi();
+        let groups = [vec![0, 2], vec![1, 3, 5], vec![4, 6], (0..7).collect()];
+        for digit in [5u32, 1_000, 50_000] {
+            for i in [0, 1, digit / 2, digit.saturating_sub(3)] {
+                let index = formula.index(digit, i);
+                for group in &groups {
+                    let Some(fused) = formula.evaluate_fused::<Sum<3>>(group, index, 3) else {
+                        continue;
+                    };
+                    let separate = group.iter().fold(Accumulator::zero(3), |sum, &term| {
+                        PolyFormula::<1, 2>::accumulate(sum, formula.evaluate_index(term, index, 3))
+                    });
+                    // Each separate term rounds down once, the fused group
+                    // once, and the integer parts differ with the signs
+                    let [_, high, low] = (fused - separate.sum()).digits;
+                    let error = group.len() as u64;
+                    assert!(
+                        high == 0 && low <= error
+                            || high == u64::MAX && low >= error.wrapping_neg(),
+                        "group {group:?} at index {i} of digit {digit}"
+                    );
+                }
+            }
+        }
+        // Indices close enough to the digit for the shift to be fractional
+        // cannot share a power of two
+        let index = formula.index(1_000, 1_000);
+        assert!(
+            formula
+                .evaluate_fused::<Sum<3>>(&[1, 6], index, 3)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn fused_evaluation_stays_within_the_error_bound() {
+        let formula = formulas::bellards_pi();
+        let fused = formula.clone().with_fusion(true);
+        for digit in [0, 100, 5_000, 20_000] {
+            let evaluation = formula.evaluate_bounded::<DynSum>(digit, 1, 4);
+            let value: DynSum = fused.evaluate_interleaved(digit, 4);
+            // Fused groups add up to a different whole number, so compare
+            // fractions; a value outside the interval wraps to a huge
+            // difference from one end
+            let fraction = |value: DynSum| value.digits[1..].to_vec();
+            let below = fraction(value.clone() - evaluation.lower());
+            let above = fraction(evaluation.upper() - value);
+            let width = fraction(evaluation.upper() - evaluation.lower());
+            assert!(below <= width && above <= width, "digit {digit}");
+        }
+    }
+
+    #[test]
+    fn fused_evaluation_ignores_chunks_and_threads() {
+        let formula = formulas::bellards_pi().with_fusion(true);
+        for digit in [3, 9_000] {
+            let expected: Sum<3> = formula.evaluate_interleaved(digit, 3);
+            for chunk in [1, 97, PLAN_BLOCK + 5, 100_000] {
+                for threads in [1, 3] {
+                    let formula = formula.clone().with_chunk(chunk);
+                    let value: Sum<3> = formula.evaluate_parallel(digit, threads, 3);
+                    assert_eq!(
+                        value.digits, expected.digits,
+                        "digit {digit}, chunk {chunk}, {threads} threads"
+                    );
+                }
+            }
+        }
+    }
+}
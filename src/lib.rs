@@ -1,140 +1,687 @@
 // SPDX-License-Identifier: MPL-2.0
 // Copyright 2025 Ethan Jaszewski
 
-use std::thread;
+use std::{
+    ops::RangeInclusive,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+};
 
 use crate::{
-    modular::{Reciprocal, mod_pow_primitive},
+    batch::LANES,
+    modular::{Barrett32, FloatModulus, Montgomery, PowMethod, Reciprocal, mod_pow_primitive},
     polynomial::Polynomial,
-    wide::Sum,
+    progress::{Cancelled, Monitor, Tracker},
+    tune::Tuning,
+    wide::{Accumulator, Fraction},
 };
 
+pub mod batch;
+pub mod checkpoint;
+pub mod digits;
+pub mod distributed;
 pub mod formulas;
+mod fusion;
+pub mod many;
 pub mod modular;
+pub mod partial;
 pub mod polynomial;
+pub mod predict;
+pub mod progress;
+pub mod shared;
+pub mod stream;
+pub mod tune;
 pub mod wide;
 
+/// Number of consecutive indices a thread claims at a time in
+/// [`PolyFormula::evaluate_parallel`], unless tuned otherwise.
+const PARALLEL_CHUNK: u32 = 1 << 14;
+
+/// Bits of precision the tail is carried past the last word of the result.
+const TAIL_GUARD_BITS: u32 = 8;
+
+/// The correction terms computed past `digit`, and a bound on the ones left
+/// out.
+#[derive(Clone, Copy, Debug)]
+pub struct Tail {
+    /// Number of terms computed past `digit`.
+    pub terms: u32,
+    /// Base-2 logarithm of an upper bound on the total magnitude of the terms
+    /// left out, in units of the integer word.
+    pub bound_log2: f64,
+}
+
+/// A value computed by [`PolyFormula::evaluate_bounded`], with a bound on
+/// how far it can be from the exact fractional part.
+#[derive(Clone, Copy, Debug)]
+pub struct Evaluation<S> {
+    pub value: S,
+    /// Upper bound on the absolute error of `value`, in units of its last
+    /// word.
+    pub error: u64,
+    pub tail: Tail,
+}
+
+impl<S: Fraction> Evaluation<S> {
+    /// Smallest value the exact result can take.
+    pub fn lower(&self) -> S {
+        self.value.clone() - S::from_lsd(self.value.words(), self.error)
+    }
+
+    /// Largest value the exact result can take.
+    pub fn upper(&self) -> S {
+        self.value.clone() + S::from_lsd(self.value.words(), self.error)
+    }
+
+    /// Number of leading fraction bits shared by every value in the error
+    /// interval, and so provably equal to those of the exact result.
+    pub fn correct_bits(&self) -> u32 {
+        let (lower, upper) = (self.lower(), self.upper());
+        let (lower, upper) = (lower.digits(), upper.digits());
+        if lower[0] != upper[0] {
+            // The interval straddles a whole number
+            return 0;
+        }
+        let mut bits = 0;
+        for (lower, upper) in lower[1..].iter().zip(&upper[1..]) {
+            let shared = (lower ^ upper).leading_zeros();
+            bits += shared;
+            if shared < u64::BITS {
+                break;
+            }
+        }
+        bits
+    }
+}
+
+/// Outcome of [`PolyFormula::evaluate_checked`], which evaluates once and
+/// retries with more words if the requested bits are ambiguous.
+#[derive(Clone, Copy, Debug)]
+pub enum Checked<S, R> {
+    /// Every requested bit was certain on the first evaluation.
+    Resolved(Evaluation<S>),
+    /// The error interval crossed a boundary within the requested bits on
+    /// the first evaluation, but not on the retry.
+    Retried(Evaluation<R>),
+    /// The error interval still crossed a boundary on the retry, so only
+    /// [`Checked::correct_bits`] of the requested bits are certain.
+    Ambiguous(Evaluation<R>),
+}
+
+impl<S: Fraction, R: Fraction> Checked<S, R> {
+    /// Evaluates with `first`, and again with `retry` if the first `bits`
+    /// fraction bits are not all certain.
+    pub(crate) fn resolve<E>(
+        bits: u32,
+        first: impl FnOnce() -> Result<Evaluation<S>, E>,
+        retry: impl FnOnce() -> Result<Evaluation<R>, E>,
+    ) -> Result<Self, E> {
+        let first = first()?;
+        if first.correct_bits() >= bits {
+            return Ok(Checked::Resolved(first));
+        }
+        let retry = retry()?;
+        Ok(if retry.correct_bits() >= bits {
+            Checked::Retried(retry)
+        } else {
+            Checked::Ambiguous(retry)
+        })
+    }
+
+    /// Fraction words of the final evaluation.
+    pub fn fraction(&self) -> &[u64] {
+        match self {
+            Checked::Resolved(evaluation) => &evaluation.value.digits()[1..],
+            Checked::Retried(evaluation) | Checked::Ambiguous(evaluation) => {
+                &evaluation.value.digits()[1..]
+            }
+        }
+    }
+
+    /// Number of leading fraction bits of the final evaluation that are
+    /// provably correct.
+    pub fn correct_bits(&self) -> u32 {
+        match self {
+            Checked::Resolved(evaluation) => evaluation.correct_bits(),
+            Checked::Retried(evaluation) | Checked::Ambiguous(evaluation) => {
+                evaluation.correct_bits()
+            }
+        }
+    }
+}
+
+impl<S: Fraction, R: Fraction> std::fmt::Display for Checked<S, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Checked::Resolved(evaluation) => {
+                write!(f, "resolved with {} words", evaluation.value.words())
+            }
+            Checked::Retried(evaluation) => write!(
+                f,
+                "ambiguous at first, resolved by retrying with {} words",
+                evaluation.value.words()
+            ),
+            Checked::Ambiguous(evaluation) => write!(
+                f,
+                "still ambiguous after retrying with {} words, only {} bits certain",
+                evaluation.value.words(),
+                evaluation.correct_bits()
+            ),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct PolyFormula<const N_DEGREE: usize, const D_DEGREE: usize> {
     alternating: bool,
     base_log2: u32,
     numerators: Vec<Polynomial<N_DEGREE>>,
     denominators: Vec<Polynomial<D_DEGREE>>,
+    scales: Vec<u32>,
+    pow_method: PowMethod,
+    fusion: bool,
+    chunk: u32,
 }
 
 impl<const N_DEGREE: usize, const D_DEGREE: usize> PolyFormula<N_DEGREE, D_DEGREE> {
+    /// Takes its exponentiation method and chunk size from
+    /// [`Tuning::current`], so the first formula made in a process reads the
+    /// tuning file, `droplet-tuning.txt` in the working directory unless
+    /// `DROPLET_TUNING` names another. Neither setting changes the values
+    /// computed.
     pub fn new(
         alternating: bool,
         base_log2: u32,
         numerators: Vec<Polynomial<N_DEGREE>>,
         denominators: Vec<Polynomial<D_DEGREE>>,
     ) -> Self {
+        // Powers of two are kept out of the denominators and applied as shifts,
+        // so the moduli used for exponentiation are as small as possible.
+        let scales: Vec<u32> = denominators
+            .iter()
+            .map(|poly| poly.trailing_zeros())
+            .collect();
+        let denominators = denominators
+            .into_iter()
+            .zip(&scales)
+            .map(|(poly, &scale)| poly >> scale)
+            .collect();
+        let tuning = Tuning::current();
         Self {
             alternating,
             base_log2,
             numerators,
             denominators,
+            scales,
+            pow_method: tuning.pow_method,
+            fusion: false,
+            chunk: tuning.chunk,
         }
     }
 
-    pub fn evaluate_term<const D: usize>(
-        &self,
-        term: usize,
-        digit: u32,
-        offset: u32,
-        stride: usize,
-    ) -> Sum<D> {
-        let numerator_poly = &self.numerators[term];
-        let denominator_poly = &self.denominators[term];
-        let mut sum = Sum::zero();
-        for i in (offset..=digit).step_by(stride) {
-            // Evaluate numerator and denominator polynomials
-            let denominator = denominator_poly.evaluate(i.into()).unsigned_abs();
-            let numerator = numerator_poly.evaluate(i.into());
-
-            // Determine if this term will be positive or negative
-            let base_positive = !self.alternating || (i & 1 == 0);
-            let term_positive = !(numerator.is_positive() ^ base_positive);
+    pub fn with_pow_method(mut self, pow_method: PowMethod) -> Self {
+        self.pow_method = pow_method;
+        self
+    }
 
-            let reciprocal = Reciprocal::new(denominator);
-            let exponent = (digit - i).into();
-            let numerator =
-                reciprocal.mod_pow_init(numerator.unsigned_abs(), 1u64 << self.base_log2, exponent);
-            let wide_numerator = Sum::from_msd(numerator);
-            let sum_term = wide_numerator / &reciprocal;
-
-            sum = if term_positive {
-                sum + sum_term
-            } else {
-                sum - sum_term
-            };
+    /// Enables fusing terms over a common denominator in
+    /// [`PolyFormula::evaluate_interleaved`] where it is estimated to pay off.
+    pub fn with_fusion(mut self, fusion: bool) -> Self {
+        self.fusion = fusion;
+        self
+    }
+
+    /// Sets the number of consecutive indices a thread claims at a time when
+    /// evaluating in parallel, which only affects how fast it runs.
+    pub fn with_chunk(mut self, chunk: u32) -> Self {
+        self.chunk = chunk.max(1);
+        self
+    }
+
+    fn mod_pow_init(&self, reciprocal: &Reciprocal, init: u64, exponent: u64) -> u64 {
+        let base = 1u64 << self.base_log2;
+        match self.pow_method {
+            PowMethod::Montgomery if reciprocal.divisor() & 1 == 1 => {
+                Montgomery::with_reciprocal(reciprocal).mod_pow_init(init, base, exponent)
+            }
+            PowMethod::Pow2 => reciprocal.mod_pow_init_pow2(init, self.base_log2, exponent),
+            PowMethod::Float if reciprocal.divisor() < FloatModulus::LIMIT => {
+                FloatModulus::new(reciprocal.divisor()).mod_pow_init(init, base, exponent)
+            }
+            _ => reciprocal.mod_pow_init(init, base, exponent),
         }
-        // Main thread will compute the correction terms
-        let num_terms = if offset == 0 {
-            (64 * D) as u32 / self.base_log2
+    }
+
+    /// The modulus reducing with [`Barrett32`] for a `denominator` below 2^32,
+    /// when the exponentiation method uses it.
+    fn narrow_modulus(&self, denominator: u64) -> Option<Barrett32> {
+        match self.pow_method {
+            PowMethod::Barrett | PowMethod::Simd => {
+                u32::try_from(denominator).ok().map(Barrett32::new)
+            }
+            _ => None,
+        }
+    }
+
+    /// Evaluates the denominator of `term` at `i`, split into its odd part
+    /// and the exponent of the power of two that was divided out.
+    fn odd_denominator(&self, term: usize, i: u32) -> (u64, u32) {
+        let denominator = self.denominators[term].evaluate(i.into()).unsigned_abs();
+        let zeros = denominator.trailing_zeros();
+        (denominator >> zeros, self.scales[term] + zeros)
+    }
+
+    /// Computes the work shared by every term at summation index `i`.
+    fn index(&self, digit: u32, i: u32) -> Index {
+        Index {
+            i,
+            positive: !self.alternating || (i & 1 == 0),
+            exponent_bits: i64::from(self.base_log2) * (i64::from(digit) - i64::from(i)),
+        }
+    }
+
+    /// Splits the power of two multiplying the numerator into whole powers of
+    /// the base and a leftover shift, after dividing out `2^scale`. When the
+    /// power is negative, as in the tail, returns the right shift to apply
+    /// instead.
+    fn split_exponent(&self, index: Index, scale: u32) -> Result<(u64, u32), u32> {
+        let base_log2 = i64::from(self.base_log2);
+        let bits = index.exponent_bits - i64::from(scale);
+        if bits >= 0 {
+            Ok(((bits / base_log2) as u64, (bits % base_log2) as u32))
         } else {
-            0
-        };
-        for i in (digit + 1)..=(digit + num_terms) {
-            // Evaluate numerator and denominator polynomials
-            let denominator = denominator_poly.evaluate(i.into()).unsigned_abs();
-            let numerator = numerator_poly.evaluate(i.into());
+            Err(bits.unsigned_abs() as u32)
+        }
+    }
 
-            // Determine if this term will be positive or negative
-            let base_positive = !self.alternating || (i & 1 == 0);
-            let term_positive = !(numerator.is_positive() ^ base_positive);
+    /// Evaluates `term` at `index`, returning whether it is added, the
+    /// numerator's magnitude, the odd part of the denominator and the power of
+    /// two divided out of it.
+    fn term_parts(&self, term: usize, index: Index) -> (bool, u64, u64, u32) {
+        // Evaluate numerator and denominator polynomials
+        let (denominator, scale) = self.odd_denominator(term, index.i);
+        let numerator = self.numerators[term].evaluate(index.i.into());
 
+        // Determine if this term will be positive or negative
+        let term_positive = !(numerator.is_positive() ^ index.positive);
+        (term_positive, numerator.unsigned_abs(), denominator, scale)
+    }
+
+    /// Computes the fractional part of `numerator * 2^exponent_bits /
+    /// (2^scale * denominator)` at `index`, for an odd `denominator`, to
+    /// `words` words.
+    fn fraction<S: Fraction>(
+        &self,
+        numerator: u64,
+        denominator: u64,
+        scale: u32,
+        index: Index,
+        words: usize,
+    ) -> S {
+        if let Some(modulus) = self.narrow_modulus(denominator) {
+            // Small denominators skip the 128-bit reciprocal entirely
+            match self.split_exponent(index, scale) {
+                Ok((exponent, shift)) => {
+                    let base = 1u64 << self.base_log2;
+                    let residue = modulus.mod_pow_init(numerator, base, exponent);
+                    S::from_msd(words, modulus.mul_pow2(residue, shift)) / &modulus
+                }
+                // Shifting a rounded quotient down rounds it the same way as
+                // dividing by the power of two exactly would, so dividing
+                // first keeps this to a single rounding
+                Err(shift) => (S::from_msd(words, numerator) / &modulus) >> shift,
+            }
+        } else {
             let reciprocal = Reciprocal::new(denominator);
+            match self.split_exponent(index, scale) {
+                Ok((exponent, shift)) => {
+                    let mut residue = self.mod_pow_init(&reciprocal, numerator, exponent);
+                    if shift > 0 {
+                        residue = (u128::from(residue) << shift) % &reciprocal;
+                    }
+                    S::from_msd(words, residue) / &reciprocal
+                }
+                Err(shift) => (S::from_msd(words, numerator) / &reciprocal) >> shift,
+            }
+        }
+    }
 
-            let shift = (i - digit) * self.base_log2;
-            let wide_numerator: Sum<_> = Sum::from_msd(numerator.unsigned_abs()) >> shift;
-            let sum_term = wide_numerator / &reciprocal;
+    /// Computes the fractional part of the contribution of `term` at `index`,
+    /// along with whether it is added or subtracted.
+    fn evaluate_index<S: Fraction>(&self, term: usize, index: Index, words: usize) -> (bool, S) {
+        let (term_positive, numerator, denominator, scale) = self.term_parts(term, index);
+        let sum_term = self.fraction(numerator, denominator, scale, index, words);
+        (term_positive, sum_term)
+    }
 
-            sum = if term_positive {
-                sum + sum_term
-            } else {
-                sum - sum_term
-            };
+    /// Evaluates `(term, index)` pairs in groups of [`LANES`], exponentiating
+    /// each group together when every denominator in it is small enough.
+    fn evaluate_batched<S: Fraction>(
+        &self,
+        pairs: impl Iterator<Item = (usize, Index)>,
+        words: usize,
+    ) -> S {
+        let mut chunk = [None; LANES];
+        let mut filled = 0;
+        let sum = pairs.fold(Accumulator::zero(words), |sum, pair| {
+            chunk[filled] = Some(pair);
+            filled += 1;
+            if filled < LANES {
+                return sum;
+            }
+            filled = 0;
+            self.evaluate_chunk::<S>(sum, std::mem::take(&mut chunk))
+        });
+        self.evaluate_chunk::<S>(sum, chunk).sum()
+    }
+
+    fn evaluate_chunk<S: Fraction>(
+        &self,
+        sum: Accumulator<S>,
+        chunk: [Option<(usize, Index)>; LANES],
+    ) -> Accumulator<S> {
+        let words = sum.words();
+        let lanes = chunk.map(|pair| {
+            let (term, index) = pair?;
+            let (term_positive, numerator, denominator, scale) = self.term_parts(term, index);
+            let (exponent, shift) = self.split_exponent(index, scale).ok()?;
+            let exponent = exponent * u64::from(self.base_log2) + u64::from(shift);
+            let init = numerator % denominator;
+            (denominator < FloatModulus::LIMIT).then_some((
+                term_positive,
+                init,
+                exponent,
+                denominator,
+            ))
+        });
+        if lanes.iter().any(Option::is_none) {
+            return chunk.into_iter().flatten().fold(sum, |sum, (term, index)| {
+                Self::accumulate(sum, self.evaluate_index::<S>(term, index, words))
+            });
+        }
+        let lanes = lanes.map(Option::unwrap);
+        let inits = lanes.map(|(_, init, _, _)| init);
+        let exponents = lanes.map(|(_, _, exponent, _)| exponent);
+        let moduli = lanes.map(|(_, _, _, denominator)| denominator);
+        let residues = batch::mod_pow2_init(inits, exponents, moduli);
+        lanes.into_iter().zip(residues).fold(
+            sum,
+            |sum, ((term_positive, _, _, denominator), residue)| {
+                let sum_term = match u32::try_from(denominator) {
+                    Ok(narrow) => S::from_msd(words, residue) / &Barrett32::new(narrow),
+                    Err(_) => S::from_msd(words, residue) / &Reciprocal::new(denominator),
+                };
+                Self::accumulate(sum, (term_positive, sum_term))
+            },
+        )
+    }
+
+    fn evaluate_pairs<S: Fraction>(
+        &self,
+        pairs: impl Iterator<Item = (usize, Index)>,
+        words: usize,
+    ) -> S {
+        match self.pow_method {
+            PowMethod::Simd => self.evaluate_batched(pairs, words),
+            _ => pairs
+                .fold(Accumulator::zero(words), |sum, (term, index)| {
+                    Self::accumulate(sum, self.evaluate_index::<S>(term, index, words))
+                })
+                .sum(),
+        }
+    }
+
+    fn accumulate<S: Fraction>(
+        mut sum: Accumulator<S>,
+        (positive, value): (bool, S),
+    ) -> Accumulator<S> {
+        if positive {
+            sum += value;
+        } else {
+            sum -= value;
         }
         sum
     }
 
-    pub fn evaluate<const D: usize>(&self, digit: u32) -> Sum<D> {
-        let mut sum = Sum::zero();
+    /// Base-2 logarithm of the total magnitude of every term at index `n`,
+    /// `|P(n)| / (2^(b(n - digit)) Q(n))` summed, for `n > digit`.
+    fn index_magnitude_log2(&self, digit: u32, n: u32) -> f64 {
+        let exponent = f64::from(self.base_log2) * f64::from(n - digit);
+        let logs: Vec<f64> = (0..self.numerators.len())
+            .filter_map(|term| {
+                let numerator = self.numerators[term].evaluate(n.into()).unsigned_abs();
+                let (denominator, scale) = self.odd_denominator(term, n);
+                (numerator != 0).then(|| {
+                    (numerator as f64).log2() - (denominator as f64).log2() - f64::from(scale)
+                })
+            })
+            .collect();
+        let largest = logs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let total: f64 = logs.iter().map(|log| (log - largest).exp2()).sum();
+        largest + total.log2() - exponent
+    }
+
+    /// Chooses how many correction terms to compute past `digit` so that the
+    /// terms left out sum to less than `2^-(64 (words - 1) + guard)`, the
+    /// last word of the result plus a few guard bits.
+    ///
+    /// Every term left out is assumed to shrink by at least a factor of the
+    /// base from one index to the next, which holds whenever `|P(n) / Q(n)|`
+    /// does not grow, as in every formula in [`formulas`].
+    pub fn tail(&self, digit: u32, words: usize) -> Tail {
+        let target = -f64::from(64 * (words as u32 - 1) + TAIL_GUARD_BITS);
+        // Bounds the rest of the series by a geometric one with ratio 2^-b
+        let geometric = -(1.0 - (-f64::from(self.base_log2)).exp2()).log2();
+        let mut terms = 0;
+        loop {
+            let bound_log2 = self.index_magnitude_log2(digit, digit + terms + 1) + geometric;
+            if bound_log2 < target {
+                return Tail { terms, bound_log2 };
+            }
+            terms += 1;
+        }
+    }
+
+    pub fn evaluate_term<S: Fraction>(
+        &self,
+        term: usize,
+        digit: u32,
+        offset: u32,
+        stride: usize,
+        words: usize,
+    ) -> S {
+        // Main thread will compute the correction terms
+        let num_terms = if offset == 0 {
+            self.tail(digit, words).terms
+        } else {
+            0
+        };
+        let main_indices = (offset..=digit).step_by(stride);
+        let tail_indices = (digit + 1)..=(digit + num_terms);
+        let sum: S = self.evaluate_pairs(main_indices.map(|i| (term, self.index(digit, i))), words);
+        sum + self.evaluate_pairs(tail_indices.map(|i| (term, self.index(digit, i))), words)
+    }
+
+    pub fn evaluate<S: Fraction>(&self, digit: u32, words: usize) -> S {
+        let mut sum = S::zero(words);
         let n_terms = self.numerators.len();
         for term in 0..n_terms {
-            let term_value = self.evaluate_term(term, digit, 0, 1);
+            let term_value = self.evaluate_term(term, digit, 0, 1, words);
             sum = sum + term_value;
         }
         sum
     }
 
-    pub fn evaluate_parallel<const D: usize>(&self, digit: u32, threads: usize) -> Sum<D> {
+    /// Evaluates every term at each index before moving to the next, sharing
+    /// the per-index work and letting batched methods fill their lanes across
+    /// terms. Returns the same value as [`PolyFormula::evaluate`] unless
+    /// fusion is enabled, which rounds once per group rather than per term.
+    pub fn evaluate_interleaved<S: Fraction>(&self, digit: u32, words: usize) -> S {
+        let indices = 0..=(digit + self.tail(digit, words).terms);
+        self.evaluate_indices(digit, indices, words)
+    }
+
+    /// Evaluates every term at each index in `indices`, as
+    /// [`PolyFormula::evaluate_interleaved`] does over all of them.
+    fn evaluate_indices<S: Fraction>(
+        &self,
+        digit: u32,
+        indices: std::ops::RangeInclusive<u32>,
+        words: usize,
+    ) -> S {
+        if self.fusion {
+            return self.evaluate_fused_indices(digit, indices, words);
+        }
         let n_terms = self.numerators.len();
-        let mut handles = Vec::with_capacity(threads);
-        for id in 0..threads {
-            let thread_formula = self.clone();
-            let handle = thread::spawn(move || {
-                let mut thread_sum: Sum<D> = Sum::zero();
-                for term in 0..n_terms {
-                    let term_value = thread_formula.evaluate_term(term, digit, id as u32, threads);
-                    thread_sum = thread_sum + term_value;
-                }
-                thread_sum
-            });
-            handles.push(handle);
+        let pairs = indices.flat_map(|i| {
+            let index = self.index(digit, i);
+            (0..n_terms).map(move |term| (term, index))
+        });
+        self.evaluate_pairs(pairs, words)
+    }
+
+    /// Bounds the error of evaluating at `digit` to `words` words. Every term
+    /// rounds its fraction down once, so it is off by less than one unit of
+    /// the last word, and carries are exact. The terms left out of the tail
+    /// add less than one more unit.
+    pub fn error_bound(&self, digit: u32, words: usize) -> (u64, Tail) {
+        let tail = self.tail(digit, words);
+        let indices = u64::from(digit) + 1 + u64::from(tail.terms);
+        let divisions = indices * self.numerators.len() as u64;
+        (divisions + 1, tail)
+    }
+
+    /// Evaluates at `digit` with [`PolyFormula::evaluate_parallel`],
+    /// along with the interval the exact result is guaranteed to lie in.
+    pub fn evaluate_bounded<S: Fraction>(
+        &self,
+        digit: u32,
+        threads: usize,
+        words: usize,
+    ) -> Evaluation<S> {
+        let value = self.evaluate_parallel(digit, threads, words);
+        let (error, tail) = self.error_bound(digit, words);
+        Evaluation { value, error, tail }
+    }
+
+    /// Evaluates at `digit` to `words` words and checks that the first `bits`
+    /// fraction bits are certain, which fails when the exact result sits so
+    /// close to a boundary, as in a long run of ones or zeros, that the error
+    /// interval crosses it. If so, evaluates again to `retry_words` words.
+    pub fn evaluate_checked<S: Fraction, R: Fraction>(
+        &self,
+        digit: u32,
+        bits: u32,
+        threads: usize,
+        words: usize,
+        retry_words: usize,
+    ) -> Checked<S, R> {
+        let Ok(checked) = Checked::<S, R>::resolve::<std::convert::Infallible>(
+            bits,
+            || Ok(self.evaluate_bounded(digit, threads, words)),
+            || Ok(self.evaluate_bounded(digit, threads, retry_words)),
+        );
+        checked
+    }
+
+    /// Evaluates at `digit` on `threads` scoped threads, which claim
+    /// [`PolyFormula::with_chunk`] consecutive indices at a time from a
+    /// shared cursor, so faster threads take on more of the work. Chunk boundaries do not
+    /// depend on which thread claims them and sums wrap exactly, so the
+    /// result is the same for any thread count and any scheduling.
+    pub fn evaluate_parallel<S: Fraction>(&self, digit: u32, threads: usize, words: usize) -> S {
+        let last = digit + self.tail(digit, words).terms;
+        self.evaluate_scheduled(digit, 0..=last, threads, words, &Monitor::new())
+            .expect("a new monitor is never cancelled")
+    }
+
+    /// [`PolyFormula::evaluate_bounded`], reporting progress to
+    /// `monitor` and stopping early if its token is cancelled.
+    pub fn evaluate_monitored<S: Fraction>(
+        &self,
+        digit: u32,
+        threads: usize,
+        words: usize,
+        monitor: &Monitor,
+    ) -> Result<Evaluation<S>, Cancelled> {
+        let last = digit + self.tail(digit, words).terms;
+        let value = self.evaluate_scheduled(digit, 0..=last, threads, words, monitor)?;
+        let (error, tail) = self.error_bound(digit, words);
+        Ok(Evaluation { value, error, tail })
+    }
+
+    /// Sums the indices in `indices` on `threads` scoped threads, in chunks
+    /// claimed from a shared cursor.
+    fn evaluate_scheduled<S: Fraction>(
+        &self,
+        digit: u32,
+        indices: RangeInclusive<u32>,
+        threads: usize,
+        words: usize,
+        monitor: &Monitor,
+    ) -> Result<S, Cancelled> {
+        let threads = threads.max(1);
+        let (first, last) = indices.into_inner();
+        let total = u64::from(last) - u64::from(first) + 1;
+        let tracker = Tracker::new(monitor, total, 0, self.numerators.len(), threads);
+        let cursor = AtomicU64::new(first.into());
+        let value = thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|id| {
+                    let (tracker, cursor) = (&tracker, &cursor);
+                    scope.spawn(move || {
+                        let mut thread_sum = S::zero(words);
+                        while !tracker.cancelled() {
+                            let start = cursor.fetch_add(self.chunk.into(), Ordering::Relaxed);
+                            let Ok(start) = u32::try_from(start) else {
+                                break;
+                            };
+                            if start > last {
+                                break;
+                            }
+                            let end = start.saturating_add(self.chunk - 1).min(last);
+                            thread_sum =
+                                thread_sum + self.evaluate_indices(digit, start..=end, words);
+                            tracker.record(id, u64::from(end - start) + 1);
+                        }
+                        thread_sum
+                    })
+                })
+                .collect();
+            handles.into_iter().fold(S::zero(words), |acc, handle| {
+                let thread_sum = handle.join().unwrap();
+                acc + thread_sum
+            })
+        });
+        tracker.finish();
+        // Every chunk claimed is summed, so the value is only incomplete if
+        // cancelling left some unclaimed
+        if cursor.into_inner() <= u64::from(last) {
+            return Err(Cancelled);
         }
-        handles.into_iter().fold(Sum::zero(), |acc, handle| {
-            let thread_sum = handle.join().unwrap();
-            acc + thread_sum
-        })
+        Ok(value)
     }
 }
 
+/// Work shared by every term at one summation index.
+#[derive(Clone, Copy)]
+struct Index {
+    i: u32,
+    positive: bool,
+    exponent_bits: i64,
+}
+
 impl<const N: usize, const D: usize> std::fmt::Display for PolyFormula<N, D> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let numerator_poly = self.numerators.iter().map(|poly| format!("{}", poly));
-        let denominator_poly = self.denominators.iter().map(|poly| format!("{}", poly));
+        let denominator_poly = self
+            .denominators
+            .iter()
+            .zip(&self.scales)
+            .map(|(poly, &scale)| match scale {
+                0 => format!("{}", poly),
+                _ => format!("{}({})", 1u64 << scale, poly),
+            });
 
         let (numerators, (denominators, division)): (Vec<_>, (Vec<_>, Vec<_>)) = numerator_poly
             .zip(denominator_poly)
@@ -148,7 +695,7 @@ impl<const N: usize, const D: usize> std::fmt::Display for PolyFormula<N, D> {
             .unzip();
 
         let base_num = if self.alternating {
-            format!("(-1)\u{207F}")
+            String::from("(-1)\u{207F}")
         } else {
             String::from("1")
         };
@@ -186,6 +733,26 @@ impl Formula {
         (self.n * i + j).pow(self.s)
     }
 
+    /// Number of correction terms past `k` needed for the terms left out to
+    /// sum to less than `2^-(bits + guard)`, using the same geometric bound
+    /// as [`PolyFormula::tail`].
+    fn tail_terms(&self, j: u32, k: u32, bits: u32) -> u32 {
+        let target = -f64::from(bits + TAIL_GUARD_BITS);
+        let log2_base = f64::from(self.b).log2();
+        let geometric = -(1.0 - (-log2_base).exp2()).log2();
+        let mut terms = 0;
+        loop {
+            let i = k + terms + 1;
+            let bound_log2 = -log2_base * f64::from(i - k)
+                - f64::from(self.denominator(i, j)).log2()
+                + geometric;
+            if bound_log2 < target {
+                return terms;
+            }
+            terms += 1;
+        }
+    }
+
     pub fn compute_term_float(&self, j: u32, k: u32) -> f64 {
         let mut sum = 0.0;
         for i in 0..=k {
@@ -195,7 +762,7 @@ impl Formula {
             let sum_term = numerator as f64 / denominator as f64;
             sum = (sum + sum_term).fract();
         }
-        let num_terms = 53 / self.b.ilog2();
+        let num_terms = self.tail_terms(j, k, f64::MANTISSA_DIGITS);
         for i in (k + 1)..=(k + num_terms) {
             let denominator = self.denominator(i, j);
             let exponent = i64::from(k) - i64::from(i);
@@ -230,7 +797,7 @@ impl Formula {
             let sum_term = widened_numerator / &reciprocal;
             sum = sum.wrapping_add(sum_term);
         }
-        let num_terms = 64 / self.b.ilog2();
+        let num_terms = self.tail_terms(j, k, u64::BITS);
         for i in (k + 1)..=(k + num_terms) {
             let denominator = self.denominator(i, j);
             let exponent = i - k;
@@ -250,7 +817,7 @@ impl Formula {
                 continue;
             }
             let j: u32 = u32::try_from(idx).unwrap() + 1;
-            let mut sum_term = a.abs() as u64 * self.compute_term_integer(j, k);
+            let mut sum_term = u64::from(a.unsigned_abs()) * self.compute_term_integer(j, k);
             if a < 0 {
                 let widened_term = (1 << 64) - u128::from(sum_term);
                 sum_term = (widened_term & u128::from(u64::MAX)) as u64;
@@ -260,3 +827,144 @@ impl Formula {
         sum
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wide::{DynSum, Sum};
+
+    const POW_METHODS: [PowMethod; 6] = [
+        PowMethod::Barrett,
+        PowMethod::Reciprocal,
+        PowMethod::Pow2,
+        PowMethod::Montgomery,
+        PowMethod::Float,
+        PowMethod::Simd,
+    ];
+
+    /// Denominators below 2^32 for one term, and crossing
+    /// [`FloatModulus::LIMIT`] around index 1000 for the other.
+    fn mixed_denominators() -> PolyFormula<1, 3> {
+        PolyFormula::new(
+            true,
+            4,
+            vec![Polynomial::new([3]), Polynomial::new([-5])],
+            vec![Polynomial::new([1, 8, 0]), Polynomial::new([7, 0, 1 << 30])],
+        )
+    }
+
+    #[test]
+    fn batched_lanes_match_scalar_evaluation() {
+        let formula = mixed_denominators();
+        for digit in [0, 3, 900, 1_100, 4_000] {
+            for words in [2, 4] {
+                let scalar: DynSum = formula.evaluate_interleaved(digit, words);
+                let simd = formula.clone().with_pow_method(PowMethod::Simd);
+                let batched: DynSum = simd.evaluate_interleaved(digit, words);
+                assert_eq!(batched.digits, scalar.digits, "interleaved at {digit}");
+                for term in 0..2 {
+                    let scalar: DynSum = formula.evaluate_term(term, digit, 0, 1, words);
+                    let batched: DynSum = simd.evaluate_term(term, digit, 0, 1, words);
+                    assert_eq!(batched.digits, scalar.digits, "term {term} at {digit}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_pow_method_gives_the_same_sum() {
+        for formula in [
+            formulas::bailey_borwein_plouffe_pi(),
+            formulas::bellards_pi(),
+        ] {
+            for digit in [0, 1, 17, 5_000] {
+                let expected: Sum<3> = formula.evaluate(digit, 3);
+                for pow_method in POW_METHODS {
+                    let formula = formula.clone().with_pow_method(pow_method);
+                    let sum: Sum<3> = formula.evaluate(digit, 3);
+                    assert_eq!(sum.digits, expected.digits, "{pow_method} at {digit}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn interleaved_matches_evaluate() {
+        let formula = formulas::bellards_pi();
+        for digit in [0, 1, 2, 17, 1_000, 5_000] {
+            let expected: Sum<3> = formula.evaluate(digit, 3);
+            for pow_method in [PowMethod::Barrett, PowMethod::Simd] {
+                let formula = formula.clone().with_pow_method(pow_method);
+                let sum: Sum<3> = formula.evaluate_interleaved(digit, 3);
+                assert_eq!(sum.digits, expected.digits, "{pow_method} at {digit}");
+            }
+        }
+    }
+
+    /// Leading fraction words of pi, after the binary point.
+    const PI: [u64; 4] = [
+        0x243f_6a88_85a3_08d3,
+        0x1319_8a2e_0370_7344,
+        0xa409_3822_299f_31d0,
+        0x082e_fa98_ec4e_6c89,
+    ];
+
+    /// Whether an exact value from `floor` up to one unit of the last word
+    /// above it lies in the interval of `evaluation`. Only fractions are
+    /// compared, as the terms drop their whole parts.
+    fn brackets(evaluation: &Evaluation<Sum<3>>, floor: Sum<3>) -> bool {
+        let fraction = |value: Sum<3>| [value.digits[1], value.digits[2]];
+        let width = fraction(evaluation.upper() - evaluation.lower());
+        let ceil = floor + Sum::from_lsd(1);
+        fraction(floor - evaluation.lower()) <= width
+            && fraction(ceil - evaluation.lower()) <= width
+    }
+
+    #[test]
+    fn exact_value_lies_within_the_error_bound() {
+        // Terms at and past the digit shift their numerators down
+        let formula = formulas::bellards_pi();
+        for digit in 0..6 {
+            let bits = 10 * digit;
+            let word = |idx: usize| match bits {
+                0 => PI[idx],
+                _ => PI[idx] << bits | PI[idx + 1] >> (64 - bits),
+            };
+            let floor = Sum {
+                digits: [0, word(0), word(1)],
+            };
+            for pow_method in POW_METHODS {
+                let formula = formula.clone().with_pow_method(pow_method);
+                let evaluation = formula.evaluate_bounded::<Sum<3>>(digit, 2, 3);
+                assert!(brackets(&evaluation, floor), "{pow_method} at {digit}");
+            }
+        }
+
+        // This formula sums to exactly zero
+        let formula = formulas::zero();
+        for digit in [0, 1, 2, 100, 7_777] {
+            let evaluation = formula.evaluate_bounded::<Sum<3>>(digit, 2, 3);
+            assert!(brackets(&evaluation, Sum { digits: [0; 3] }), "{digit}");
+        }
+    }
+
+    #[test]
+    fn checked_evaluation_retries_when_too_narrow() {
+        let formula = formulas::bellards_pi();
+        let wide: Evaluation<Sum<3>> = formula.evaluate_bounded(1_000, 1, 3);
+        // One fraction word settles 40 bits, but not 100
+        let checked = formula.evaluate_checked::<Sum<2>, Sum<3>>(1_000, 40, 1, 2, 3);
+        assert!(matches!(checked, Checked::Resolved(_)), "{checked}");
+        assert_eq!(checked.fraction()[0] >> 24, wide.value.digits[1] >> 24);
+
+        let checked = formula.evaluate_checked::<Sum<2>, Sum<3>>(1_000, 100, 1, 2, 3);
+        assert!(matches!(checked, Checked::Retried(_)), "{checked}");
+        assert_eq!(checked.fraction(), &wide.value.digits[1..]);
+        assert!(checked.correct_bits() >= 100);
+
+        // Two fraction words cannot settle every one of their bits
+        let checked = formula.evaluate_checked::<Sum<2>, Sum<3>>(1_000, 128, 1, 2, 3);
+        assert!(matches!(checked, Checked::Ambiguous(_)), "{checked}");
+        assert!(checked.correct_bits() < 128);
+    }
+}
@@ -3,7 +3,7 @@
 
 use std::time::{Duration, Instant};
 
-use droplet::{Formula, formulas, wide::Sum};
+use droplet::{Formula, PolyFormula, formulas, modular::PowMethod, wide::Sum};
 
 fn main() {
     let hex_idx = 5_000_000;
@@ -35,7 +35,7 @@ fn main() {
     let mut poly_val = Sum::zero();
     for _ in 0..iters {
         let uint_start = Instant::now();
-        poly_val = poly_formula.evaluate::<2>(digit_idx);
+        poly_val = poly_formula.evaluate::<Sum<2>>(digit_idx, 2);
         poly_time += uint_start.elapsed();
     }
 
@@ -50,4 +50,56 @@ fn main() {
         uint_time / iters,
         poly_time / iters
     );
+
+    let workloads = [
+        ("BBP", formulas::bailey_borwein_plouffe_pi(), hex_idx),
+        ("Bellard", formulas::bellards_pi(), hex_idx / 5 * 2),
+    ];
+    for (name, formula, digit) in workloads {
+        let barrett_time = time_pow_method(&formula, PowMethod::Barrett, digit, iters);
+        let reciprocal_time = time_pow_method(&formula, PowMethod::Reciprocal, digit, iters);
+        let pow2_time = time_pow_method(&formula, PowMethod::Pow2, digit, iters);
+        let montgomery_time = time_pow_method(&formula, PowMethod::Montgomery, digit, iters);
+        let float_time = time_pow_method(&formula, PowMethod::Float, digit, iters);
+        let simd_time = time_pow_method(&formula, PowMethod::Simd, digit, iters);
+        println!(
+            "{name}: Barrett: {barrett_time:?}, Reciprocal: {reciprocal_time:?}, Pow2: {pow2_time:?}, Montgomery: {montgomery_time:?}, Float: {float_time:?}, Simd: {simd_time:?}"
+        );
+
+        let formula = formula.with_pow_method(PowMethod::Simd);
+        let mut interleaved_time = Duration::ZERO;
+        for _ in 0..iters {
+            let start = Instant::now();
+            let _: Sum<2> = formula.evaluate_interleaved(digit, 2);
+            interleaved_time += start.elapsed();
+        }
+        println!("{name}: Interleaved Simd: {:?}", interleaved_time / iters);
+
+        let formula = formula
+            .with_pow_method(PowMethod::Reciprocal)
+            .with_fusion(true);
+        let mut fused_time = Duration::ZERO;
+        for _ in 0..iters {
+            let start = Instant::now();
+            let _: Sum<2> = formula.evaluate_interleaved(digit, 2);
+            fused_time += start.elapsed();
+        }
+        println!("{name}: Fused: {:?}", fused_time / iters);
+    }
+}
+
+fn time_pow_method(
+    formula: &PolyFormula<1, 2>,
+    pow_method: PowMethod,
+    digit: u32,
+    iters: u32,
+) -> Duration {
+    let formula = formula.clone().with_pow_method(pow_method);
+    let mut time = Duration::ZERO;
+    for _ in 0..iters {
+        let start = Instant::now();
+        let _: Sum<2> = formula.evaluate(digit, 2);
+        time += start.elapsed();
+    }
+    time / iters
 }
@@ -0,0 +1,220 @@
+use std::{
+    ops::RangeInclusive,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use crate::{Evaluation, PolyFormula, Tail, wide::Fraction};
+
+/// Evaluations streamed back by [`PolyFormula::evaluate_many`] as they
+/// finish, each with the position of its digit in the list asked for.
+/// Dropping it stops the threads once they finish the chunk they are on.
+#[derive(Debug)]
+pub struct Many<S> {
+    results: mpsc::Receiver<(usize, Evaluation<S>)>,
+    stop: Arc<AtomicBool>,
+    handles: Vec<thread::JoinHandle<()>>,
+}
+
+impl<S> Iterator for Many<S> {
+    type Item = (usize, Evaluation<S>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.results.recv() {
+            Ok(result) => Some(result),
+            // Every thread has exited, so a panic in one of them would
+            // otherwise just leave evaluations missing
+            Err(_) => {
+                self.join();
+                None
+            }
+        }
+    }
+}
+
+impl<S> Many<S> {
+    /// Waits for the threads to exit, resuming the first panic among them.
+    fn join(&mut self) {
+        for handle in self.handles.drain(..) {
+            if let Err(panic) = handle.join() {
+                std::panic::resume_unwind(panic);
+            }
+        }
+    }
+}
+
+impl<S> Drop for Many<S> {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if thread::panicking() {
+            return;
+        }
+        self.join();
+    }
+}
+
+/// One digit of a [`Many`], with its sum so far and the number of indices
+/// still to be added to it.
+struct Job<S> {
+    digit: u32,
+    last: u32,
+    error: u64,
+    tail: Tail,
+    state: Mutex<(S, u64)>,
+}
+
+/// Everything the threads of a [`Many`] share.
+struct Shared<const N_DEGREE: usize, const D_DEGREE: usize, S> {
+    formula: PolyFormula<N_DEGREE, D_DEGREE>,
+    words: usize,
+    jobs: Vec<Job<S>>,
+    /// The job and index the next chunk starts at.
+    cursor: Mutex<(usize, u32)>,
+    stop: Arc<AtomicBool>,
+}
+
+impl<const N_DEGREE: usize, const D_DEGREE: usize, S: Fraction> Shared<N_DEGREE, D_DEGREE, S> {
+    /// Claims the next chunk of indices, moving through the jobs in order so
+    /// the earliest finish first.
+    fn claim(&self) -> Option<(usize, RangeInclusive<u32>)> {
+        let mut cursor = self.cursor.lock().unwrap();
+        let (job, start) = *cursor;
+        let last = self.jobs.get(job)?.last;
+        let end = start.saturating_add(self.formula.chunk - 1).min(last);
+        *cursor = match end.checked_add(1) {
+            Some(next) if end < last => (job, next),
+            _ => (job + 1, 0),
+        };
+        Some((job, start..=end))
+    }
+
+    fn work(&self, results: &mpsc::Sender<(usize, Evaluation<S>)>) {
+        while !self.stop.load(Ordering::Relaxed) {
+            let Some((index, indices)) = self.claim() else {
+                break;
+            };
+            let job = &self.jobs[index];
+            let summed = u64::from(indices.end() - indices.start()) + 1;
+            let value: S = self
+                .formula
+                .evaluate_indices(job.digit, indices, self.words);
+
+            let mut state = job.state.lock().unwrap();
+            let (sum, remaining) = &mut *state;
+            *sum = std::mem::replace(sum, S::zero(self.words)) + value;
+            *remaining -= summed;
+            if *remaining == 0 {
+                let evaluation = Evaluation {
+                    value: std::mem::replace(sum, S::zero(self.words)),
+                    error: job.error,
+                    tail: job.tail,
+                };
+                // Nothing is left to do if the results are no longer wanted
+                if results.send((index, evaluation)).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl<const N_DEGREE: usize, const D_DEGREE: usize> PolyFormula<N_DEGREE, D_DEGREE> {
+    /// Evaluates at each of `digits` to `words` words like
+    /// [`PolyFormula::evaluate_bounded`], on one pool of `threads`
+    /// threads claiming chunks of indices across every digit. Each
+    /// evaluation is returned as soon as it finishes, with its position in
+    /// `digits`, mostly in order.
+    pub fn evaluate_many<S: Fraction>(
+        &self,
+        digits: impl IntoIterator<Item = u32>,
+        threads: usize,
+        words: usize,
+    ) -> Many<S> {
+        let jobs = digits
+            .into_iter()
+            .map(|digit| {
+                let (error, tail) = self.error_bound(digit, words);
+                let last = digit + tail.terms;
+                Job {
+                    digit,
+                    last,
+                    error,
+                    tail,
+                    state: Mutex::new((S::zero(words), u64::from(last) + 1)),
+                }
+            })
+            .collect();
+        let stop = Arc::new(AtomicBool::new(false));
+        let shared = Arc::new(Shared {
+            formula: self.clone(),
+            words,
+            jobs,
+            cursor: Mutex::new((0, 0)),
+            stop: stop.clone(),
+        });
+
+        let (sender, results) = mpsc::channel();
+        let handles = (0..threads.max(1))
+            .map(|_| {
+                let (shared, sender) = (shared.clone(), sender.clone());
+                thread::spawn(move || shared.work(&sender))
+            })
+            .collect();
+        Many {
+            results,
+            stop,
+            handles,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PolyFormula, formulas, polynomial::Polynomial, wide::Sum};
+
+    #[test]
+    fn many_matches_bounded_evaluation() {
+        let digits = [0, 1, 1_000, 7, 4_096, 1, 300];
+        for chunk in [1, 7, 1_000, 1 << 14] {
+            let formula = formulas::bellards_pi().with_chunk(chunk);
+            let mut seen = vec![false; digits.len()];
+            for (idx, evaluation) in formula.evaluate_many::<Sum<3>>(digits, 3, 3) {
+                let expected = formula.evaluate_bounded::<Sum<3>>(digits[idx], 1, 3);
+                assert_eq!(
+                    evaluation.value.digits, expected.value.digits,
+                    "chunk {chunk}"
+                );
+                assert_eq!(evaluation.error, expected.error);
+                assert_eq!(evaluation.tail.terms, expected.tail.terms);
+                assert!(!seen[idx], "digit {idx} returned twice");
+                seen[idx] = true;
+            }
+            assert!(seen.iter().all(|&seen| seen), "chunk {chunk}");
+        }
+
+        let formula = formulas::bellards_pi();
+        assert_eq!(formula.evaluate_many::<Sum<3>>([], 3, 3).count(), 0);
+    }
+
+    #[test]
+    fn worker_panics_reach_the_caller() {
+        // The denominator is zero at the first index
+        let formula = PolyFormula::new(
+            false,
+            4,
+            vec![Polynomial::new([1])],
+            vec![Polynomial::new([0, 1])],
+        )
+        .with_chunk(16);
+        let collected = std::panic::catch_unwind(|| {
+            formula
+                .evaluate_many::<Sum<3>>([100, 200, 300], 2, 3)
+                .count()
+        });
+        assert!(collected.is_err());
+    }
+}
@@ -25,7 +25,7 @@ where
         return 0;
     }
     let mut result = 1;
-    base = base % modulus;
+    base %= modulus;
     while exponent > 0 {
         if exponent % 2 == 1 {
             result = mod_mul_primitive(result, base, modulus);
@@ -44,10 +44,19 @@ pub struct Reciprocal {
 }
 
 impl Reciprocal {
+    /// Computes the reciprocal of `divisor` from scratch. Consecutive
+    /// denominators are close enough to derive one reciprocal from the last
+    /// with a correction instead, but that is a serial chain of products
+    /// which measured about 34ns a divisor against 22ns for this on x86-64,
+    /// so every term computes its own.
     pub fn new(divisor: u64) -> Self {
         divisor.into()
     }
 
+    pub fn divisor(&self) -> u64 {
+        self.divisor >> self.shift
+    }
+
     fn divide_impl(&self, dividend: u128) -> (u64, u64) {
         let dividend_lo = dividend as u64;
         let dividend_hi = (dividend >> 64) as u64;
@@ -72,6 +81,13 @@ impl Reciprocal {
         (quotient_hi, remainder)
     }
 
+    /// Computes the quotient and remainder of `dividend` together, for
+    /// `dividend < divisor * 2^64`.
+    pub fn div_rem(&self, dividend: u128) -> (u64, u64) {
+        let (quotient, remainder) = self.divide_impl(dividend << self.shift);
+        (quotient, remainder >> self.shift)
+    }
+
     pub fn mod_pow(&self, base: u64, exponent: u64) -> u64 {
         self.mod_pow_init(1, base, exponent)
     }
@@ -81,7 +97,7 @@ impl Reciprocal {
         result = self.divide_impl(result).1.into();
         while exponent > 0 {
             if exponent % 2 == 1 {
-                let dividend = u128::from(result) * u128::from(base);
+                let dividend = result * u128::from(base);
                 result = self.divide_impl(dividend).1.into();
             }
             exponent >>= 1;
@@ -91,28 +107,40 @@ impl Reciprocal {
         (result >> self.shift) as u64
     }
 
-    pub fn mod_pow_init_pow2(&self, init: u64, log2base: u32, mut exponent: u64) -> u64 {
-        let mut result = 1u128 << self.shift;
-        exponent = exponent << exponent.leading_zeros();
-        while exponent > 0 {
-            let dividend = u128::from(result) * u128::from(result >> self.shift);
-            result = self.divide_impl(dividend).1.into();
-            exponent = exponent.rotate_left(1);
-            if exponent & 1 > 0 {
-                let dividend = u128::from(result) << log2base;
-                result = self.divide_impl(dividend).1.into();
+    /// Computes `init * (2^log2base)^exponent` left-to-right, so multiplying
+    /// by the base is a shift rather than a full product. When the modulus
+    /// leaves at least `log2base` bits of headroom the shift is folded into
+    /// the squaring, leaving one reduction per exponent bit.
+    pub fn mod_pow_init_pow2(&self, init: u64, log2base: u32, exponent: u64) -> u64 {
+        let mut result = 1u64 << self.shift;
+        let bits = u64::BITS - exponent.leading_zeros();
+        if self.shift >= log2base {
+            for bit in (0..bits).rev() {
+                // Branch-free: the exponent bits are effectively random
+                let multiply_shift = ((exponent >> bit) & 1) as u32 * log2base;
+                let dividend = u128::from(result) * u128::from(result >> self.shift);
+                result = self.divide_impl(dividend << multiply_shift).1;
+            }
+        } else {
+            for bit in (0..bits).rev() {
+                let dividend = u128::from(result) * u128::from(result >> self.shift);
+                result = self.divide_impl(dividend).1;
+                if (exponent >> bit) & 1 == 1 {
+                    let dividend = u128::from(result) << log2base;
+                    result = self.divide_impl(dividend).1;
+                }
             }
-            exponent &= !1;
         }
         let dividend = u128::from(result) * u128::from(init);
-        result = self.divide_impl(dividend).1.into();
-        (result >> self.shift) as u64
+        result = self.divide_impl(dividend).1;
+        result >> self.shift
     }
 }
 
 impl std::ops::Div<&Reciprocal> for u64 {
     type Output = u64;
 
+    #[allow(clippy::suspicious_arithmetic_impl)]
     fn div(self, rhs: &Reciprocal) -> Self::Output {
         let dividend = u128::from(self) << rhs.shift;
         rhs.divide_impl(dividend).0
@@ -131,6 +159,7 @@ impl std::ops::Rem<&Reciprocal> for u64 {
 impl std::ops::Div<&Reciprocal> for u128 {
     type Output = u64;
 
+    #[allow(clippy::suspicious_arithmetic_impl)]
     fn div(self, rhs: &Reciprocal) -> Self::Output {
         let dividend = self << rhs.shift;
         rhs.divide_impl(dividend).0
@@ -165,8 +194,8 @@ impl std::convert::From<u64> for Reciprocal {
         let error = (1u128 << 96)
             .wrapping_sub(u128::from(reciprocal_2) * u128::from(msb_63))
             .wrapping_add(u128::from((reciprocal_2 >> 1) * lsb_1));
-        let reciprocal_3 =
-            (reciprocal_2 << 31) + (error.wrapping_mul(reciprocal_2.into()) >> 65) as u64;
+        let reciprocal_3 = (reciprocal_2 << 31)
+            .wrapping_add((error.wrapping_mul(reciprocal_2.into()) >> 65) as u64);
         let reciprocal = reciprocal_3.wrapping_sub(
             ((u128::from(reciprocal_3) + (1 << 64) + 1).wrapping_mul(divisor.into()) >> 64) as u64,
         );
@@ -177,3 +206,691 @@ impl std::convert::From<u64> for Reciprocal {
         }
     }
 }
+
+/// Montgomery arithmetic modulo an odd `u64`.
+///
+/// Exponentiation keeps only the base in Montgomery form: multiplying a
+/// plain residue by a Montgomery-form one yields a plain residue, so the
+/// conversion in happens once per call and no conversion out is needed.
+#[derive(Debug)]
+pub struct Montgomery {
+    modulus: u64,
+    inverse: u64,
+    r_squared: u64,
+}
+
+impl Montgomery {
+    pub fn new(modulus: u64) -> Self {
+        debug_assert!(modulus & 1 == 1, "Montgomery modulus must be odd");
+        let r = 0u64.wrapping_sub(modulus) % modulus;
+        let r_squared = (u128::from(r) * u128::from(r) % u128::from(modulus)) as u64;
+        Self::with_r_squared(modulus, r_squared)
+    }
+
+    /// Builds the context reusing an existing reciprocal of the same modulus
+    /// to compute `R² mod m`, avoiding a full 128-bit hardware division.
+    pub fn with_reciprocal(reciprocal: &Reciprocal) -> Self {
+        let modulus = reciprocal.divisor();
+        debug_assert!(modulus & 1 == 1, "Montgomery modulus must be odd");
+        let r = 0u64.wrapping_sub(modulus) % modulus;
+        let r_squared = (u128::from(r) * u128::from(r)) % reciprocal;
+        Self::with_r_squared(modulus, r_squared)
+    }
+
+    fn with_r_squared(modulus: u64, r_squared: u64) -> Self {
+        // Newton iteration doubles the number of correct bits each step,
+        // starting from the 3 bits that m * m = 1 (mod 8) gives for free.
+        let mut inverse = modulus;
+        for _ in 0..5 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(modulus.wrapping_mul(inverse)));
+        }
+        Self {
+            modulus,
+            inverse,
+            r_squared,
+        }
+    }
+
+    /// Computes `value * R⁻¹ mod m` for `value < m * 2⁶⁴`.
+    fn reduce(&self, value: u128) -> u64 {
+        let quotient = (value as u64).wrapping_mul(self.inverse);
+        let correction = ((u128::from(quotient) * u128::from(self.modulus)) >> 64) as u64;
+        let (result, borrow) = ((value >> 64) as u64).overflowing_sub(correction);
+        if borrow {
+            result.wrapping_add(self.modulus)
+        } else {
+            result
+        }
+    }
+
+    fn multiply(&self, lhs: u64, rhs: u64) -> u64 {
+        self.reduce(u128::from(lhs) * u128::from(rhs))
+    }
+
+    pub fn mod_pow(&self, base: u64, exponent: u64) -> u64 {
+        self.mod_pow_init(1, base, exponent)
+    }
+
+    pub fn mod_pow_init(&self, init: u64, base: u64, mut exponent: u64) -> u64 {
+        let mut base = self.multiply(base % self.modulus, self.r_squared);
+        let mut result = init;
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                result = self.multiply(result, base);
+            }
+            exponent >>= 1;
+            base = self.multiply(base, base);
+        }
+        if result >= self.modulus {
+            result %= self.modulus;
+        }
+        result
+    }
+}
+
+/// Barrett reduction modulo a `u32`, for denominators small enough that the
+/// 128-bit [`Reciprocal`] is unnecessary.
+///
+/// With `factor = ⌊(2⁶⁴ - 1) / m⌋`, the estimated quotient of any dividend
+/// below `m * 2³²` is at most one too small, so one correction suffices.
+#[derive(Debug)]
+pub struct Barrett32 {
+    modulus: u64,
+    factor: u64,
+}
+
+impl Barrett32 {
+    pub fn new(modulus: u32) -> Self {
+        let modulus = u64::from(modulus);
+        Self {
+            modulus,
+            factor: u64::MAX / modulus,
+        }
+    }
+
+    /// Returns the quotient and remainder of `dividend`, which must be below
+    /// `m * 2³²`.
+    pub fn div_rem(&self, dividend: u64) -> (u64, u64) {
+        let quotient = ((u128::from(dividend) * u128::from(self.factor)) >> 64) as u64;
+        let remainder = dividend - quotient * self.modulus;
+        if remainder >= self.modulus {
+            (quotient + 1, remainder - self.modulus)
+        } else {
+            (quotient, remainder)
+        }
+    }
+
+    fn reduce(&self, value: u64) -> u64 {
+        if value >> 32 < self.modulus {
+            self.div_rem(value).1
+        } else {
+            value % self.modulus
+        }
+    }
+
+    /// Computes `value * 2^shift mod m` for `value < m`.
+    pub fn mul_pow2(&self, mut value: u64, mut shift: u32) -> u64 {
+        while shift > 0 {
+            let step = shift.min(32);
+            value = self.div_rem(value << step).1;
+            shift -= step;
+        }
+        value
+    }
+
+    pub fn mod_pow(&self, base: u64, exponent: u64) -> u64 {
+        self.mod_pow_init(1, base, exponent)
+    }
+
+    pub fn mod_pow_init(&self, init: u64, base: u64, mut exponent: u64) -> u64 {
+        let mut result = self.reduce(init);
+        let mut base = self.reduce(base);
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                result = self.div_rem(result * base).1;
+            }
+            exponent >>= 1;
+            base = self.div_rem(base * base).1;
+        }
+        result
+    }
+}
+
+/// Double-precision modular arithmetic for moduli below [`FloatModulus::LIMIT`].
+///
+/// The quotient of each product is estimated in `f64` and the remainder is
+/// recovered exactly with wrapping integer arithmetic. For `a, b < m < 2⁵⁰`
+/// the three roundings in `a * b * (1 / m)` give a relative error of at most
+/// `3 * 2⁻⁵³`, so the estimate is within `0.375` of `a * b / m` and the
+/// truncated quotient is off by at most one. The remainder therefore lies in
+/// `[-m, 2m)`, which a single correction in each direction brings into range.
+#[derive(Debug)]
+pub struct FloatModulus {
+    modulus: u64,
+    inverse: f64,
+}
+
+impl FloatModulus {
+    pub const LIMIT: u64 = 1 << 50;
+
+    pub fn new(modulus: u64) -> Self {
+        debug_assert!(modulus > 0 && modulus < Self::LIMIT);
+        Self {
+            modulus,
+            inverse: 1.0 / modulus as f64,
+        }
+    }
+
+    fn multiply(&self, lhs: u64, rhs: u64) -> u64 {
+        let modulus = self.modulus as i64;
+        // Everything is below 2^50, so the cheaper signed conversions are exact
+        let product = lhs as i64 as f64 * rhs as i64 as f64;
+        let quotient = (product * self.inverse) as i64 as u64;
+        let remainder = lhs
+            .wrapping_mul(rhs)
+            .wrapping_sub(quotient.wrapping_mul(self.modulus)) as i64;
+        // Branch-free corrections from [-m, 2m) to [0, m)
+        let remainder = remainder + ((remainder >> 63) & modulus) - modulus;
+        (remainder + ((remainder >> 63) & modulus)) as u64
+    }
+
+    pub fn mod_pow(&self, base: u64, exponent: u64) -> u64 {
+        self.mod_pow_init(1, base, exponent)
+    }
+
+    pub fn mod_pow_init(&self, init: u64, mut base: u64, mut exponent: u64) -> u64 {
+        let mut result = init % self.modulus;
+        base %= self.modulus;
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                result = self.multiply(result, base);
+            }
+            exponent >>= 1;
+            base = self.multiply(base, base);
+        }
+        result
+    }
+}
+
+/// Full 256-bit product of two `u128`s, as `(high, low)`.
+fn mul_wide(lhs: u128, rhs: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (lhs_lo, lhs_hi) = (lhs & MASK, lhs >> 64);
+    let (rhs_lo, rhs_hi) = (rhs & MASK, rhs >> 64);
+    let lo_lo = lhs_lo * rhs_lo;
+    let lo_hi = lhs_lo * rhs_hi;
+    let hi_lo = lhs_hi * rhs_lo;
+    let middle = (lo_lo >> 64) + (lo_hi & MASK) + (hi_lo & MASK);
+    let low = (lo_lo & MASK) | (middle << 64);
+    let high = lhs_hi * rhs_hi + (lo_hi >> 64) + (hi_lo >> 64) + (middle >> 64);
+    (high, low)
+}
+
+/// Arithmetic modulo an odd `u128` above `u64::MAX`, for fused denominators
+/// too wide for the 64-bit types.
+///
+/// Exponentiation runs in Montgomery form with `R = 2¹²⁸`, and long division
+/// uses Knuth's normalized quotient estimate from the top divisor word.
+#[derive(Debug)]
+pub struct Modulus128 {
+    modulus: u128,
+    inverse: u128,
+    one: u128,
+    shift: u32,
+    top: Reciprocal,
+}
+
+impl Modulus128 {
+    pub fn new(modulus: u128) -> Self {
+        debug_assert!(modulus & 1 == 1, "Modulus128 modulus must be odd");
+        debug_assert!(
+            modulus > u128::from(u64::MAX),
+            "Modulus128 modulus must be wide"
+        );
+        let mut inverse = modulus;
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul(2u128.wrapping_sub(modulus.wrapping_mul(inverse)));
+        }
+        let shift = modulus.leading_zeros();
+        Self {
+            modulus,
+            inverse,
+            one: 0u128.wrapping_sub(modulus) % modulus,
+            shift,
+            top: Reciprocal::new(((modulus << shift) >> 64) as u64),
+        }
+    }
+
+    pub fn modulus(&self) -> u128 {
+        self.modulus
+    }
+
+    /// Computes `(high * R + low) * R⁻¹ mod m` for a value below `m * R`.
+    fn reduce(&self, (high, low): (u128, u128)) -> u128 {
+        let quotient = low.wrapping_mul(self.inverse);
+        let (correction, _) = mul_wide(quotient, self.modulus);
+        let (result, borrow) = high.overflowing_sub(correction);
+        if borrow {
+            result.wrapping_add(self.modulus)
+        } else {
+            result
+        }
+    }
+
+    fn double(&self, value: u128) -> u128 {
+        let complement = self.modulus - value;
+        if value >= complement {
+            value - complement
+        } else {
+            value + value
+        }
+    }
+
+    /// Computes `init * 2^exponent mod m` for `init < m`, left-to-right so
+    /// multiplying by the base is a modular doubling.
+    pub fn mod_pow2_init(&self, init: u128, exponent: u64) -> u128 {
+        let mut result = self.one;
+        for bit in (0..u64::BITS - exponent.leading_zeros()).rev() {
+            result = self.reduce(mul_wide(result, result));
+            if (exponent >> bit) & 1 == 1 {
+                result = self.double(result);
+            }
+        }
+        // Montgomery times plain is plain, so this also converts out
+        self.reduce(mul_wide(result, init))
+    }
+
+    /// Returns the quotient and remainder of `high * 2⁶⁴ + low`, where
+    /// `high < m`.
+    pub fn div_rem(&self, high: u128, low: u64) -> (u64, u128) {
+        let divisor = self.modulus << self.shift;
+        let divisor_top = (divisor >> 64) as u64;
+        let dividend_top = (high << self.shift) | (u128::from(low) >> (64 - self.shift));
+        let dividend_low = low << self.shift;
+
+        // Estimate is at most two too large, since the divisor is normalized
+        let mut quotient = if (dividend_top >> 64) as u64 >= divisor_top {
+            u64::MAX
+        } else {
+            self.top.divide_impl(dividend_top).0
+        };
+        let product_low = u128::from(quotient) * (divisor & u128::from(u64::MAX));
+        let mut product_top = u128::from(quotient) * u128::from(divisor_top) + (product_low >> 64);
+        let mut product_low = product_low as u64;
+        while (product_top, product_low) > (dividend_top, dividend_low) {
+            quotient -= 1;
+            let (low, borrow) = product_low.overflowing_sub(divisor as u64);
+            product_low = low;
+            product_top -= u128::from(divisor_top) + u128::from(borrow);
+        }
+        let (remainder_low, borrow) = dividend_low.overflowing_sub(product_low);
+        let remainder_top = dividend_top - product_top - u128::from(borrow);
+        let remainder = (remainder_top << 64) | u128::from(remainder_low);
+        (quotient, remainder >> self.shift)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PowMethod {
+    /// Right-to-left exponentiation reducing with [`Barrett32`] for moduli
+    /// below 2^32, falling back to [`PowMethod::Reciprocal`] for larger ones.
+    #[default]
+    Barrett,
+    /// Right-to-left exponentiation reducing with [`Reciprocal`].
+    Reciprocal,
+    /// Left-to-right exponentiation with [`Reciprocal::mod_pow_init_pow2`].
+    Pow2,
+    /// Montgomery exponentiation for odd moduli, falling back to
+    /// [`PowMethod::Reciprocal`] for even ones.
+    Montgomery,
+    /// Double-precision exponentiation for moduli below
+    /// [`FloatModulus::LIMIT`], falling back to [`PowMethod::Reciprocal`]
+    /// for larger ones. On x86-64 it is about a quarter slower than
+    /// [`PowMethod::Reciprocal`] for Bellard's formula at digits 10^6 and
+    /// 10^7, since the 64-bit products it avoids are cheap there. It can only
+    /// pay off where those products are slow next to double-precision
+    /// multiplication, so leave the choice to [`crate::PolyFormula::autotune`].
+    Float,
+    /// Lane-batched double-precision exponentiation of consecutive indices
+    /// with [`crate::batch::mod_pow2_init`], for moduli below
+    /// [`FloatModulus::LIMIT`], falling back to [`PowMethod::Barrett`] for
+    /// groups that cannot be batched.
+    Simd,
+}
+
+impl std::str::FromStr for PowMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "barrett" => Ok(Self::Barrett),
+            "reciprocal" => Ok(Self::Reciprocal),
+            "pow2" => Ok(Self::Pow2),
+            "montgomery" => Ok(Self::Montgomery),
+            "float" => Ok(Self::Float),
+            "simd" => Ok(Self::Simd),
+            _ => Err(format!("unknown exponentiation method: {s}")),
+        }
+    }
+}
+
+impl std::fmt::Display for PowMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Barrett => "barrett",
+            Self::Reciprocal => "reciprocal",
+            Self::Pow2 => "pow2",
+            Self::Montgomery => "montgomery",
+            Self::Float => "float",
+            Self::Simd => "simd",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random words, so failures reproduce.
+    fn splitmix(state: &mut u64) -> u64 {
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// `init * base^exponent mod modulus` in plain 128-bit arithmetic.
+    fn reference_pow(init: u64, base: u64, exponent: u64, modulus: u64) -> u64 {
+        let power = mod_pow_primitive::<u128>(base, exponent, modulus);
+        (u128::from(init) * u128::from(power) % u128::from(modulus)) as u64
+    }
+
+    /// `(lhs + rhs) mod modulus` for operands below an odd `u128` modulus.
+    fn add_mod(lhs: u128, rhs: u128, modulus: u128) -> u128 {
+        let complement = modulus - lhs;
+        if rhs >= complement {
+            rhs - complement
+        } else {
+            lhs + rhs
+        }
+    }
+
+    /// `lhs * rhs mod modulus` by shifting and adding one bit at a time.
+    fn reference_mul_mod(lhs: u128, rhs: u128, modulus: u128) -> u128 {
+        (0..u128::BITS).rev().fold(0, |product, bit| {
+            let product = add_mod(product, product, modulus);
+            if (rhs >> bit) & 1 == 1 {
+                add_mod(product, lhs, modulus)
+            } else {
+                product
+            }
+        })
+    }
+
+    /// `init * 2^exponent mod modulus` by square and multiply.
+    fn reference_pow2(init: u128, exponent: u64, modulus: u128) -> u128 {
+        let mut power = 1;
+        for bit in (0..u64::BITS).rev() {
+            power = reference_mul_mod(power, power, modulus);
+            if (exponent >> bit) & 1 == 1 {
+                power = add_mod(power, power, modulus);
+            }
+        }
+        reference_mul_mod(init, power, modulus)
+    }
+
+    /// Quotient and remainder of `high * 2^64 + low` by restoring division.
+    fn reference_div_rem(high: u128, low: u64, modulus: u128) -> (u64, u128) {
+        (0..u64::BITS)
+            .rev()
+            .fold((0, high), |(quotient, remainder), bit| {
+                // Doubling subtracts the modulus once exactly when this holds
+                let mut carry = u64::from(remainder >= modulus - remainder);
+                let mut remainder = add_mod(remainder, remainder, modulus);
+                if (low >> bit) & 1 == 1 {
+                    remainder = add_mod(remainder, 1, modulus);
+                    carry |= u64::from(remainder == 0);
+                }
+                ((quotient << 1) | carry, remainder)
+            })
+    }
+
+    /// Moduli just above 2^64, near 2^127 and near 2^128, and random ones
+    /// in between.
+    fn wide_moduli(state: &mut u64) -> Vec<u128> {
+        let fixed = [
+            (1 << 64) + 1,
+            (1 << 64) + 13,
+            (1 << 65) - 1,
+            (1 << 96) + 61,
+            (1 << 127) - 1,
+            (1 << 127) + 1,
+            u128::MAX - 2,
+            u128::MAX,
+        ];
+        let random = (0..32).map(|_| {
+            let value = (u128::from(splitmix(state)) << 64) | u128::from(splitmix(state));
+            (value >> (splitmix(state) % 64)) | (1 << 64) | 1
+        });
+        fixed.into_iter().chain(random).collect()
+    }
+
+    #[test]
+    fn mul_wide_matches_plain_arithmetic() {
+        let mut state = 33;
+        for _ in 0..1000 {
+            let (lhs, rhs) = (splitmix(&mut state), splitmix(&mut state));
+            let product = u128::from(lhs) * u128::from(rhs);
+            assert_eq!(mul_wide(lhs.into(), rhs.into()), (0, product));
+
+            let lhs = (u128::from(splitmix(&mut state)) << 64) | u128::from(lhs);
+            let rhs = (u128::from(splitmix(&mut state)) << 64) | u128::from(rhs);
+            let (high, low) = mul_wide(lhs, rhs);
+            assert_eq!(low, lhs.wrapping_mul(rhs));
+            // The high word is the product's quotient by 2^128, which a
+            // modulus of 2^128 - 1 exposes: high + low ≡ lhs * rhs
+            let modulus = u128::MAX;
+            let expected = reference_mul_mod(lhs % modulus, rhs % modulus, modulus);
+            assert_eq!(add_mod(high % modulus, low % modulus, modulus), expected);
+            assert!(high <= lhs);
+        }
+        assert_eq!(mul_wide(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+    }
+
+    #[test]
+    fn modulus128_matches_plain_arithmetic() {
+        let mut state = 33;
+        for modulus in wide_moduli(&mut state) {
+            let wide = Modulus128::new(modulus);
+            for _ in 0..16 {
+                let init = ((u128::from(splitmix(&mut state)) << 64)
+                    | u128::from(splitmix(&mut state)))
+                    % modulus;
+                for exponent in [0, 1, 2, 63, 64, 127, 128, 1000, splitmix(&mut state)] {
+                    assert_eq!(
+                        wide.mod_pow2_init(init, exponent),
+                        reference_pow2(init, exponent, modulus),
+                        "{init} * 2^{exponent} mod {modulus}"
+                    );
+                }
+
+                let high = ((u128::from(splitmix(&mut state)) << 64)
+                    | u128::from(splitmix(&mut state)))
+                    % modulus;
+                for (high, low) in [
+                    (high, splitmix(&mut state)),
+                    (0, splitmix(&mut state)),
+                    (modulus - 1, u64::MAX),
+                ] {
+                    assert_eq!(
+                        wide.div_rem(high, low),
+                        reference_div_rem(high, low, modulus),
+                        "({high} * 2^64 + {low}) / {modulus}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn barrett32_matches_plain_arithmetic() {
+        let mut state = 30;
+        let random: Vec<u32> = (0..64)
+            .map(|_| (splitmix(&mut state) >> (32 + splitmix(&mut state) % 31)) as u32)
+            .filter(|&modulus| modulus > 1)
+            .collect();
+        for modulus in [2, 3, 7, 65_537, (1 << 31) - 1, u32::MAX - 4, u32::MAX]
+            .into_iter()
+            .chain(random)
+        {
+            let barrett = Barrett32::new(modulus);
+            let m = u64::from(modulus);
+            for _ in 0..64 {
+                let dividend = splitmix(&mut state) % (m << 32);
+                assert_eq!(barrett.div_rem(dividend), (dividend / m, dividend % m));
+
+                let value = splitmix(&mut state) % m;
+                let shift = (splitmix(&mut state) % 100) as u32;
+                let expected = reference_pow(value, 2, shift.into(), m);
+                assert_eq!(
+                    barrett.mul_pow2(value, shift),
+                    expected,
+                    "{value} << {shift} mod {m}"
+                );
+
+                let (init, base) = (splitmix(&mut state), splitmix(&mut state));
+                for exponent in [0, 1, splitmix(&mut state) % 1000, splitmix(&mut state)] {
+                    assert_eq!(
+                        barrett.mod_pow_init(init, base, exponent),
+                        reference_pow(init, base, exponent, m),
+                        "{init} * {base}^{exponent} mod {m}"
+                    );
+                }
+            }
+            assert_eq!(barrett.div_rem((m << 32) - 1), (((m << 32) - 1) / m, m - 1));
+        }
+    }
+
+    #[test]
+    fn mod_pow_init_pow2_matches_mod_pow_init() {
+        let mut state = 28;
+        // Small divisors leave enough headroom to fold the shift into the
+        // squaring; those near 2^64 take the fallback
+        let fixed = [
+            2,
+            3,
+            1_000_003,
+            (1 << 40) + 15,
+            (1 << 54) - 33,
+            (1 << 63) + 1,
+            u64::MAX - 58,
+            u64::MAX,
+        ];
+        let random: Vec<u64> = (0..64)
+            .map(|_| splitmix(&mut state) >> (splitmix(&mut state) % 62))
+            .filter(|&divisor| divisor > 1)
+            .collect();
+        for divisor in fixed.into_iter().chain(random) {
+            let reciprocal = Reciprocal::new(divisor);
+            for log2base in [1, 4, 10, 20] {
+                let base = 1 << log2base;
+                for exponent in [0, 1, 2, 3, 1000, splitmix(&mut state), u64::MAX] {
+                    let init = splitmix(&mut state);
+                    let expected = reference_pow(init, base, exponent, divisor);
+                    assert_eq!(
+                        reciprocal.mod_pow_init(init, base, exponent),
+                        expected,
+                        "mod_pow_init({init}, 2^{log2base}, {exponent}) mod {divisor}"
+                    );
+                    assert_eq!(
+                        reciprocal.mod_pow_init_pow2(init, log2base, exponent),
+                        expected,
+                        "mod_pow_init_pow2({init}, {log2base}, {exponent}) mod {divisor}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn montgomery_matches_mod_pow_primitive() {
+        let mut state = 26;
+        // The reduction's headroom is smallest for moduli near 2^64
+        let fixed = [
+            1,
+            3,
+            1_000_003,
+            (1 << 32) + 15,
+            (1 << 63) - 25,
+            (1 << 63) + 1,
+            u64::MAX - 58,
+            u64::MAX - 2,
+            u64::MAX,
+        ];
+        let random: Vec<u64> = (0..64)
+            .map(|_| (splitmix(&mut state) >> (splitmix(&mut state) % 62)) | 1)
+            .collect();
+        for modulus in fixed.into_iter().chain(random) {
+            let montgomery = Montgomery::new(modulus);
+            let shared = Montgomery::with_reciprocal(&Reciprocal::new(modulus));
+            for _ in 0..16 {
+                let (init, base) = (splitmix(&mut state), splitmix(&mut state));
+                for exponent in [0, 1, 2, splitmix(&mut state) % 1000, splitmix(&mut state)] {
+                    let expected = reference_pow(init, base, exponent, modulus);
+                    assert_eq!(
+                        montgomery.mod_pow_init(init, base, exponent),
+                        expected,
+                        "{init} * {base}^{exponent} mod {modulus}"
+                    );
+                    assert_eq!(shared.mod_pow_init(init, base, exponent), expected);
+                }
+            }
+            assert_eq!(
+                montgomery.mod_pow(u64::MAX, u64::MAX),
+                mod_pow_primitive::<u128>(u64::MAX, u64::MAX, modulus)
+            );
+        }
+    }
+
+    #[test]
+    fn float_modulus_matches_mod_pow_primitive() {
+        let mut state = 29;
+        // The quotient estimate is loosest for moduli just under the limit
+        let limit = FloatModulus::LIMIT;
+        let fixed = [
+            1,
+            2,
+            3,
+            1_000_003,
+            (1 << 32) + 15,
+            limit / 2 + 1,
+            limit - 27,
+            limit - 2,
+            limit - 1,
+        ];
+        let random: Vec<u64> = (0..64)
+            .map(|_| splitmix(&mut state) >> (14 + splitmix(&mut state) % 48))
+            .filter(|&modulus| modulus > 0)
+            .collect();
+        for modulus in fixed.into_iter().chain(random) {
+            let float = FloatModulus::new(modulus);
+            for _ in 0..16 {
+                let (init, base) = (splitmix(&mut state), splitmix(&mut state));
+                for exponent in [0, 1, 2, splitmix(&mut state) % 1000, splitmix(&mut state)] {
+                    assert_eq!(
+                        float.mod_pow_init(init, base, exponent),
+                        reference_pow(init, base, exponent, modulus),
+                        "{init} * {base}^{exponent} mod {modulus}"
+                    );
+                }
+                // Residues just below the modulus give the largest products
+                let top = modulus - 1 - splitmix(&mut state) % modulus.min(16);
+                assert_eq!(
+                    float.mod_pow(top, 3),
+                    mod_pow_primitive::<u128>(top, 3, modulus)
+                );
+            }
+        }
+    }
+}
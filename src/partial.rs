@@ -0,0 +1,263 @@
+use std::ops::Range;
+
+use crate::{Evaluation, PolyFormula, Tail, wide::Fraction};
+
+/// The sum of every term over some of the indices for one digit, computed by
+/// [`PolyFormula::evaluate_range`] or [`PolyFormula::evaluate_tail`].
+/// Partial sums over disjoint indices merge into the same value however the
+/// indices were split up and in whatever order they are merged.
+#[derive(Clone, Debug)]
+pub struct Partial<S> {
+    pub digit: u32,
+    pub value: S,
+    /// Indices up to `digit` summed into `value`, sorted, disjoint and with
+    /// adjacent ranges joined.
+    pub ranges: Vec<Range<u32>>,
+    /// The correction terms past `digit` summed into `value`, if any.
+    pub tail: Option<Tail>,
+    /// Number of terms summed into `value`, each rounded down once.
+    pub divisions: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeError {
+    /// The partial sums are for different digits.
+    Digit(u32, u32),
+    /// The partial sums have different widths.
+    Words(usize, usize),
+    /// Both partial sums include the index.
+    Overlap(u32),
+    /// Both partial sums include the correction terms.
+    Tail,
+}
+
+impl std::fmt::Display for MergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeError::Digit(a, b) => write!(f, "partial sums are for digits {a} and {b}"),
+            MergeError::Words(a, b) => write!(f, "partial sums have {a} and {b} words"),
+            MergeError::Overlap(i) => write!(f, "both partial sums include index {i}"),
+            MergeError::Tail => write!(f, "both partial sums include the correction terms"),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// A range of indices passed to [`PolyFormula::evaluate_range`] that goes
+/// past the digit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeError {
+    pub digit: u32,
+    pub range: Range<u32>,
+}
+
+impl std::fmt::Display for RangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self { digit, range } = self;
+        write!(f, "range {range:?} goes past digit {digit}")
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+impl<S: Fraction> Partial<S> {
+    /// The sum over no indices, to `words` words.
+    pub fn zero(digit: u32, words: usize) -> Self {
+        Self {
+            digit,
+            value: S::zero(words),
+            ranges: Vec::new(),
+            tail: None,
+            divisions: 0,
+        }
+    }
+
+    /// Combines two partial sums for the same digit over disjoint indices.
+    pub fn merge(mut self, other: Self) -> Result<Self, MergeError> {
+        self.merge_mut(other)?;
+        Ok(self)
+    }
+
+    /// Adds `other` into this partial sum in place, leaving it unchanged on
+    /// error. Each range of `other` is placed by binary search, so merging
+    /// small sums into a large one does not copy its ranges.
+    pub fn merge_mut(&mut self, other: Self) -> Result<(), MergeError> {
+        if self.digit != other.digit {
+            return Err(MergeError::Digit(self.digit, other.digit));
+        }
+        if self.value.words() != other.value.words() {
+            return Err(MergeError::Words(self.value.words(), other.value.words()));
+        }
+        if self.tail.is_some() && other.tail.is_some() {
+            return Err(MergeError::Tail);
+        }
+        for range in other.ranges.iter().filter(|range| !range.is_empty()) {
+            let idx = self
+                .ranges
+                .partition_point(|found| found.end <= range.start);
+            if let Some(found) = self.ranges.get(idx)
+                && found.start < range.end
+            {
+                return Err(MergeError::Overlap(found.start.max(range.start)));
+            }
+        }
+
+        for range in other.ranges.into_iter().filter(|range| !range.is_empty()) {
+            // The first range that ends at or after this one starts
+            let idx = self.ranges.partition_point(|found| found.end < range.start);
+            match self.ranges.get(idx) {
+                Some(found) if found.end == range.start => {
+                    self.ranges[idx].end = range.end;
+                    if self
+                        .ranges
+                        .get(idx + 1)
+                        .is_some_and(|next| next.start == range.end)
+                    {
+                        self.ranges[idx].end = self.ranges.remove(idx + 1).end;
+                    }
+                }
+                Some(found) if found.start == range.end => self.ranges[idx].start = range.start,
+                _ => self.ranges.insert(idx, range),
+            }
+        }
+
+        let words = self.value.words();
+        self.value = std::mem::replace(&mut self.value, S::zero(words)) + other.value;
+        self.tail = self.tail.or(other.tail);
+        self.divisions += other.divisions;
+        Ok(())
+    }
+
+    /// Whether every index up to the digit and the correction terms have
+    /// been summed.
+    pub fn is_complete(&self) -> bool {
+        self.tail.is_some()
+            && matches!(self.ranges.as_slice(), [range] if range.start == 0 && range.end == self.digit + 1)
+    }
+
+    /// The full evaluation with its error bound, once every index up to the
+    /// digit and the correction terms have been summed.
+    pub fn into_evaluation(self) -> Option<Evaluation<S>> {
+        if !self.is_complete() {
+            return None;
+        }
+        Some(Evaluation {
+            value: self.value,
+            // One more unit for the terms left out of the tail
+            error: self.divisions + 1,
+            tail: self.tail?,
+        })
+    }
+}
+
+impl<const N_DEGREE: usize, const D_DEGREE: usize> PolyFormula<N_DEGREE, D_DEGREE> {
+    /// Sums every term over the indices in `range` to `words` words, or
+    /// fails if the range goes past `digit`. Sums over disjoint ranges and
+    /// the tail merge to exactly [`PolyFormula::evaluate_interleaved`].
+    pub fn evaluate_range<S: Fraction>(
+        &self,
+        digit: u32,
+        range: Range<u32>,
+        words: usize,
+    ) -> Result<Partial<S>, RangeError> {
+        if u64::from(range.end) > u64::from(digit) + 1 {
+            return Err(RangeError { digit, range });
+        }
+        let (value, ranges) = if range.is_empty() {
+            (S::zero(words), Vec::new())
+        } else {
+            let value = self.evaluate_indices(digit, range.start..=(range.end - 1), words);
+            (value, vec![range.clone()])
+        };
+        Ok(Partial {
+            digit,
+            value,
+            ranges,
+            tail: None,
+            divisions: range.len() as u64 * self.numerators.len() as u64,
+        })
+    }
+
+    /// Sums the correction terms past `digit` chosen by
+    /// [`PolyFormula::tail`], to `words` words.
+    pub fn evaluate_tail<S: Fraction>(&self, digit: u32, words: usize) -> Partial<S> {
+        let tail = self.tail(digit, words);
+        let value = if tail.terms == 0 {
+            S::zero(words)
+        } else {
+            self.evaluate_indices(digit, (digit + 1)..=(digit + tail.terms), words)
+        };
+        Partial {
+            digit,
+            value,
+            ranges: Vec::new(),
+            tail: Some(tail),
+            divisions: u64::from(tail.terms) * self.numerators.len() as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{formulas, wide::Sum};
+
+    #[test]
+    fn ranges_merge_to_interleaved() {
+        let formula = formulas::bellards_pi();
+        let digit = 1_000;
+        let expected: Sum<3> = formula.evaluate_interleaved(digit, 3);
+        let partial = [0..1, 600..1_001, 1..600]
+            .into_iter()
+            .map(|range| formula.evaluate_range::<Sum<3>>(digit, range, 3).unwrap())
+            .fold(formula.evaluate_tail(digit, 3), |partial, chunk| {
+                partial.merge(chunk).unwrap()
+            });
+        let evaluation = partial.into_evaluation().unwrap();
+        assert_eq!(evaluation.value.digits, expected.digits);
+    }
+
+    #[test]
+    fn merge_mut_joins_adjacent_ranges() {
+        let formula = formulas::bellards_pi();
+        let chunk = |range| formula.evaluate_range::<Sum<2>>(100, range, 2).unwrap();
+        let mut partial = chunk(40..50);
+        for range in [0..10, 60..70, 50..60, 20..30, 10..15, 16..20] {
+            partial.merge_mut(chunk(range)).unwrap();
+        }
+        assert_eq!(partial.ranges, [0..15, 16..30, 40..70]);
+        assert_eq!(
+            partial.merge_mut(chunk(25..41)),
+            Err(MergeError::Overlap(25))
+        );
+        assert_eq!(
+            partial.merge_mut(chunk(30..41)),
+            Err(MergeError::Overlap(40))
+        );
+        assert_eq!(partial.ranges, [0..15, 16..30, 40..70]);
+
+        partial.merge_mut(chunk(15..16)).unwrap();
+        partial.merge_mut(chunk(30..40)).unwrap();
+        partial.merge_mut(chunk(70..101)).unwrap();
+        partial.merge_mut(formula.evaluate_tail(100, 2)).unwrap();
+        let expected: Sum<2> = formula.evaluate_interleaved(100, 2);
+        assert_eq!(
+            partial.into_evaluation().unwrap().value.digits,
+            expected.digits
+        );
+    }
+
+    #[test]
+    fn range_past_the_digit_is_an_error() {
+        let formula = formulas::bellards_pi();
+        assert!(formula.evaluate_range::<Sum<2>>(10, 0..11, 2).is_ok());
+        assert_eq!(
+            formula.evaluate_range::<Sum<2>>(10, 5..12, 2).unwrap_err(),
+            RangeError {
+                digit: 10,
+                range: 5..12
+            }
+        );
+    }
+}
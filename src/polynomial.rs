@@ -17,6 +17,16 @@ impl<const DEGREE: usize> Polynomial<DEGREE> {
         }
         result
     }
+
+    /// Largest power of two dividing every coefficient.
+    pub fn trailing_zeros(&self) -> u32 {
+        self.coefficients
+            .iter()
+            .filter(|&&coeff| coeff != 0)
+            .map(|coeff| coeff.trailing_zeros())
+            .min()
+            .unwrap_or(0)
+    }
 }
 
 impl<const N: usize> std::convert::From<[i32; N]> for Polynomial<N> {
@@ -28,12 +38,22 @@ impl<const N: usize> std::convert::From<[i32; N]> for Polynomial<N> {
 impl<const DEGREE: usize> std::ops::Mul<i32> for Polynomial<DEGREE> {
     type Output = Self;
 
-    fn mul(self, rhs: i32) -> Self::Output {
-        let mut result = self.clone();
-        for coeff in result.coefficients.iter_mut() {
+    fn mul(mut self, rhs: i32) -> Self::Output {
+        for coeff in self.coefficients.iter_mut() {
             *coeff *= rhs;
         }
-        result
+        self
+    }
+}
+
+impl<const DEGREE: usize> std::ops::Shr<u32> for Polynomial<DEGREE> {
+    type Output = Self;
+
+    fn shr(mut self, rhs: u32) -> Self::Output {
+        for coeff in self.coefficients.iter_mut() {
+            *coeff >>= rhs;
+        }
+        self
     }
 }
 
@@ -0,0 +1,286 @@
+use std::time::{Duration, Instant};
+
+use crate::{PolyFormula, wide::Fraction};
+
+/// Number of consecutive indices timed in each sample.
+const SAMPLE_INDICES: u32 = 1024;
+
+/// Times each sample is repeated, keeping the fastest to filter out noise.
+const SAMPLE_REPEATS: usize = 3;
+
+/// Distances from the digit of the samples near it, where the exponents are
+/// shortest and the fit learns how the cost grows with their length.
+const SAMPLE_DISTANCES: [u32; 5] = [0, 1 << 6, 1 << 12, 1 << 18, 1 << 24];
+
+/// Fractions of the digit at which the remaining samples start, where the
+/// denominators span the sizes the evaluation will meet.
+const SAMPLE_FRACTIONS: [f64; 3] = [0.0, 1.0 / 3.0, 2.0 / 3.0];
+
+/// Time taken by one term at one index, fitted to a fixed cost and a cost
+/// per bit of the exponent, which is what exponentiation scales with.
+#[derive(Clone, Copy, Debug)]
+pub struct CostModel {
+    pub fixed_ns: f64,
+    pub per_bit_ns: f64,
+}
+
+impl CostModel {
+    pub fn cost_ns(&self, exponent_bits: u32) -> f64 {
+        (self.fixed_ns + self.per_bit_ns * f64::from(exponent_bits)).max(0.0)
+    }
+
+    /// Least-squares fit to `(exponent bits, nanoseconds)` samples, falling
+    /// back to their mean when the bits do not vary or the slope comes out
+    /// negative.
+    fn fit(samples: &[(f64, f64)]) -> Self {
+        let n = samples.len() as f64;
+        let mean_x = samples.iter().map(|&(x, _)| x).sum::<f64>() / n;
+        let mean_y = samples.iter().map(|&(_, y)| y).sum::<f64>() / n;
+        let covariance: f64 = samples
+            .iter()
+            .map(|&(x, y)| (x - mean_x) * (y - mean_y))
+            .sum();
+        let variance: f64 = samples.iter().map(|&(x, _)| (x - mean_x).powi(2)).sum();
+        let per_bit_ns = if variance > 0.0 {
+            (covariance / variance).max(0.0)
+        } else {
+            0.0
+        };
+        Self {
+            fixed_ns: mean_y - per_bit_ns * mean_x,
+            per_bit_ns,
+        }
+    }
+}
+
+/// Estimated cost of evaluating at a digit, from
+/// [`PolyFormula::predict`].
+#[derive(Clone, Debug)]
+pub struct Prediction {
+    pub threads: usize,
+    /// Estimated time until the evaluation finishes, assuming every thread
+    /// has a core to itself.
+    pub wall_time: Duration,
+    /// Estimated time summed over every thread.
+    pub cpu_time: Duration,
+    /// Estimated time spent on each term, summed over every thread.
+    pub term_times: Vec<Duration>,
+    /// The term taking the most time.
+    pub dominant_term: usize,
+    /// The fitted cost of each term.
+    pub models: Vec<CostModel>,
+}
+
+impl std::fmt::Display for Prediction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.1?} on {} threads ({:.1?} in total)",
+            self.wall_time, self.threads, self.cpu_time
+        )?;
+        // A formula without terms has none to name
+        if let Some(term_time) = self.term_times.get(self.dominant_term) {
+            let share =
+                term_time.as_secs_f64() / self.cpu_time.as_secs_f64().max(f64::MIN_POSITIVE);
+            write!(
+                f,
+                ", {:.0}% of it in term {}",
+                100.0 * share,
+                self.dominant_term
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N_DEGREE: usize, const D_DEGREE: usize> PolyFormula<N_DEGREE, D_DEGREE> {
+    /// Bit length of the exponent used at index `i` when evaluating at
+    /// `digit`, ignoring the few bits the denominator's power of two removes.
+    fn exponent_bits(&self, digit: u32, i: u32) -> u32 {
+        let exponent = u64::from(self.base_log2) * u64::from(digit - i);
+        u64::BITS - exponent.leading_zeros()
+    }
+
+    /// Times `term` over `SAMPLE_INDICES` indices from `start`, in
+    /// nanoseconds per index.
+    fn time_sample<S: Fraction>(&self, term: usize, digit: u32, start: u32, words: usize) -> f64 {
+        let end = start.saturating_add(SAMPLE_INDICES).min(digit + 1);
+        (0..SAMPLE_REPEATS)
+            .map(|_| {
+                let pairs = (start..end).map(|i| (term, self.index(digit, i)));
+                let timer = Instant::now();
+                let sum: S = self.evaluate_pairs(pairs, words);
+                std::hint::black_box(sum);
+                timer.elapsed().as_nanos() as f64 / f64::from(end - start)
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Fits the cost of each term, as evaluated by
+    /// [`PolyFormula::evaluate_term`], from short samples timed at a
+    /// few indices before `digit`.
+    pub fn calibrate<S: Fraction>(&self, digit: u32, words: usize) -> Vec<CostModel> {
+        let last_start = (digit + 1).saturating_sub(SAMPLE_INDICES);
+        let near = SAMPLE_DISTANCES
+            .iter()
+            .map(|&distance| last_start.saturating_sub(distance));
+        let spread = SAMPLE_FRACTIONS
+            .iter()
+            .map(|&fraction| (f64::from(last_start) * fraction) as u32);
+        let mut starts: Vec<u32> = near.chain(spread).collect();
+        starts.sort_unstable();
+        starts.dedup();
+
+        (0..self.numerators.len())
+            .map(|term| {
+                let samples: Vec<(f64, f64)> = starts
+                    .iter()
+                    .map(|&start| {
+                        // Attribute the sample to the exponent at its middle
+                        let middle = start.saturating_add(SAMPLE_INDICES / 2).min(digit);
+                        let bits = f64::from(self.exponent_bits(digit, middle));
+                        (bits, self.time_sample::<S>(term, digit, start, words))
+                    })
+                    .collect();
+                CostModel::fit(&samples)
+            })
+            .collect()
+    }
+
+    /// Predicts how long evaluating at `digit` to `words` words takes on
+    /// `threads` threads, by calibrating on this machine and summing the
+    /// fitted cost over every index. The exponent at index `i` has about
+    /// `log2(b (digit - i))` bits, so the total grows as `digit log digit`.
+    ///
+    /// Each term is timed on its own, so the saving from fusing terms is not
+    /// included.
+    pub fn predict<S: Fraction>(&self, digit: u32, threads: usize, words: usize) -> Prediction {
+        self.prediction(self.calibrate::<S>(digit, words), digit, threads)
+    }
+
+    /// Sums the cost `models` give every term over every index up to
+    /// `digit`.
+    fn prediction(&self, models: Vec<CostModel>, digit: u32, threads: usize) -> Prediction {
+        // Count the indices at each exponent length: the exponent at index i
+        // is b (digit - i), which has k bits for distances in a closed range
+        let base = u64::from(self.base_log2);
+        let buckets: Vec<(u32, f64)> = (0..=u64::BITS)
+            .filter_map(|bits| {
+                let (low, high) = match bits {
+                    0 => (0, 0),
+                    _ => (
+                        (1u128 << (bits - 1)).div_ceil(base.into()),
+                        ((1u128 << bits) - 1) / u128::from(base),
+                    ),
+                };
+                let high = high.min(u128::from(digit));
+                (low <= high).then(|| (bits, (high - low + 1) as f64))
+            })
+            .collect();
+
+        let term_ns: Vec<f64> = models
+            .iter()
+            .map(|model| {
+                buckets
+                    .iter()
+                    .map(|&(bits, count)| model.cost_ns(bits) * count)
+                    .sum()
+            })
+            .collect();
+        let cpu_ns: f64 = term_ns.iter().sum();
+        let dominant_term = (0..term_ns.len())
+            .max_by(|&a, &b| term_ns[a].total_cmp(&term_ns[b]))
+            .unwrap_or(0);
+        let threads = threads.max(1);
+        Prediction {
+            threads,
+            wall_time: Duration::from_secs_f64(cpu_ns / threads as f64 / 1e9),
+            cpu_time: Duration::from_secs_f64(cpu_ns / 1e9),
+            term_times: term_ns
+                .iter()
+                .map(|&ns| Duration::from_secs_f64(ns / 1e9))
+                .collect(),
+            dominant_term,
+            models,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{formulas, polynomial::Polynomial, wide::Sum};
+
+    #[test]
+    fn fit_recovers_a_line() {
+        let samples: Vec<(f64, f64)> = [4.0, 10.0, 20.0, 33.0]
+            .map(|bits| (bits, 40.0 + 2.5 * bits))
+            .to_vec();
+        let model = CostModel::fit(&samples);
+        assert!((model.fixed_ns - 40.0).abs() < 1e-9, "{model:?}");
+        assert!((model.per_bit_ns - 2.5).abs() < 1e-9, "{model:?}");
+        assert!((model.cost_ns(50) - 165.0).abs() < 1e-9);
+
+        // No spread in the bits, or timings that fall with them, give the
+        // mean
+        let model = CostModel::fit(&[(8.0, 10.0), (8.0, 30.0)]);
+        assert_eq!((model.fixed_ns, model.per_bit_ns), (20.0, 0.0));
+        let model = CostModel::fit(&[(8.0, 30.0), (16.0, 10.0)]);
+        assert_eq!((model.fixed_ns, model.per_bit_ns), (20.0, 0.0));
+    }
+
+    #[test]
+    fn prediction_sums_the_models_over_every_index() {
+        let formula = formulas::bellards_pi();
+        let digit = 100_000;
+        let models: Vec<CostModel> = (0..7)
+            .map(|term| CostModel {
+                fixed_ns: 50.0 * f64::from(term),
+                per_bit_ns: 3.0,
+            })
+            .collect();
+        let prediction = formula.prediction(models.clone(), digit, 4);
+
+        let term_ns = |model: &CostModel| -> f64 {
+            (0..=digit)
+                .map(|i| model.cost_ns(formula.exponent_bits(digit, i)))
+                .sum()
+        };
+        let expected: f64 = models.iter().map(term_ns).sum();
+        let close = |time: Duration, ns: f64| (time.as_secs_f64() * 1e9 - ns).abs() < 1e-6 * ns;
+        assert!(close(prediction.cpu_time, expected), "{prediction}");
+        assert!(close(prediction.wall_time, expected / 4.0), "{prediction}");
+        for (time, model) in prediction.term_times.iter().zip(&models) {
+            assert!(close(*time, term_ns(model)));
+        }
+        assert_eq!(prediction.dominant_term, 6);
+    }
+
+    #[test]
+    fn calibration_fits_every_term() {
+        let formula = formulas::bellards_pi();
+        let models = formula.calibrate::<Sum<3>>(50_000, 3);
+        assert_eq!(models.len(), 7);
+        for model in &models {
+            assert!(
+                model.per_bit_ns >= 0.0 && model.cost_ns(20) > 0.0,
+                "{model:?}"
+            );
+        }
+        let prediction = formula.predict::<Sum<3>>(50_000, 2, 3);
+        assert!(prediction.cpu_time > Duration::ZERO);
+        assert!(prediction.to_string().contains("on 2 threads"));
+    }
+
+    #[test]
+    fn formulas_without_terms_predict_nothing() {
+        let formula: PolyFormula<1, 2> =
+            PolyFormula::new(false, 4, Vec::<Polynomial<1>>::new(), Vec::new());
+        let prediction = formula.predict::<Sum<3>>(10_000, 1, 3);
+        assert_eq!(prediction.cpu_time, Duration::ZERO);
+        assert_eq!(
+            prediction.to_string(),
+            "0.0ns on 1 threads (0.0ns in total)"
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use std::{
+    sync::{
+        Arc, Mutex, OnceLock,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Stops an evaluation once cancelled, as soon as each of its threads
+/// finishes the indices it is summing. Clones share the same state.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// A token cancelled on Ctrl-C, SIGINT on Unix, replacing the default
+    /// handling, which terminates the process. Every call returns the same
+    /// token. It is never cancelled if something else already handles
+    /// Ctrl-C.
+    pub fn sigint() -> Self {
+        static SIGINT: OnceLock<CancelToken> = OnceLock::new();
+
+        SIGINT
+            .get_or_init(|| {
+                let token = CancelToken::new();
+                let handler = token.clone();
+                // Runs on a thread of its own rather than in the signal handler
+                let _ = ctrlc::set_handler(move || handler.cancel());
+                token
+            })
+            .clone()
+    }
+}
+
+/// Returned by an evaluation stopped with a [`CancelToken`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("evaluation was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// How far an evaluation has got, counted in summation indices. Every term
+/// is evaluated at each index, so each term has reached the same point.
+#[derive(Clone, Debug)]
+pub struct Progress {
+    /// Indices summed so far, including those resumed from a checkpoint.
+    pub done: u64,
+    pub total: u64,
+    /// Indices already summed when the evaluation started.
+    pub resumed: u64,
+    /// Number of terms evaluated at each index.
+    pub terms: usize,
+    /// Indices summed by each thread since the evaluation started.
+    pub per_thread: Vec<u64>,
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    pub fn fraction(&self) -> f64 {
+        self.done as f64 / self.total.max(1) as f64
+    }
+
+    /// Time left at the rate observed since the evaluation started, once
+    /// there is one.
+    pub fn eta(&self) -> Option<Duration> {
+        let summed = self.done - self.resumed;
+        if summed == 0 {
+            return None;
+        }
+        let rate = summed as f64 / self.elapsed.as_secs_f64();
+        Some(Duration::from_secs_f64(
+            self.total.saturating_sub(self.done) as f64 / rate,
+        ))
+    }
+}
+
+type Report = Arc<dyn Fn(&Progress) + Send + Sync>;
+
+/// Reports the progress of an evaluation at an interval and carries the
+/// token that cancels it.
+#[derive(Clone)]
+pub struct Monitor {
+    cancel: CancelToken,
+    report: Option<Report>,
+    interval: Duration,
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        Self {
+            cancel: CancelToken::new(),
+            report: None,
+            interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl std::fmt::Debug for Monitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Monitor")
+            .field("cancel", &self.cancel)
+            .field("report", &self.report.is_some())
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+impl Monitor {
+    /// Reports nothing and is never cancelled, until configured otherwise.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Calls `report` from one of the evaluating threads at most once per
+    /// interval, and once more when the evaluation ends.
+    pub fn with_callback(mut self, report: impl Fn(&Progress) + Send + Sync + 'static) -> Self {
+        self.report = Some(Arc::new(report));
+        self
+    }
+
+    /// Sends each report to `sender` instead of calling back.
+    pub fn with_channel(self, sender: mpsc::Sender<Progress>) -> Self {
+        self.with_callback(move |progress| {
+            let _ = sender.send(progress.clone());
+        })
+    }
+
+    /// Reports once a second by default.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn cancel_token(&self) -> &CancelToken {
+        &self.cancel
+    }
+}
+
+/// Counts the indices each thread of one evaluation has summed and reports
+/// them through a [`Monitor`].
+pub(crate) struct Tracker<'a> {
+    monitor: &'a Monitor,
+    start: Instant,
+    total: u64,
+    resumed: u64,
+    terms: usize,
+    per_thread: Vec<AtomicU64>,
+    reported: Mutex<Instant>,
+}
+
+impl<'a> Tracker<'a> {
+    pub(crate) fn new(
+        monitor: &'a Monitor,
+        total: u64,
+        resumed: u64,
+        terms: usize,
+        threads: usize,
+    ) -> Self {
+        let start = Instant::now();
+        Self {
+            monitor,
+            start,
+            total,
+            resumed,
+            terms,
+            per_thread: (0..threads).map(|_| AtomicU64::new(0)).collect(),
+            reported: Mutex::new(start),
+        }
+    }
+
+    pub(crate) fn cancelled(&self) -> bool {
+        self.monitor.cancel.is_cancelled()
+    }
+
+    /// Counts `indices` more summed by `thread`, reporting if the interval
+    /// has passed and no other thread is already reporting.
+    pub(crate) fn record(&self, thread: usize, indices: u64) {
+        self.per_thread[thread].fetch_add(indices, Ordering::Relaxed);
+        let Some(report) = &self.monitor.report else {
+            return;
+        };
+        if let Ok(mut reported) = self.reported.try_lock()
+            && reported.elapsed() >= self.monitor.interval
+        {
+            report(&self.progress());
+            *reported = Instant::now();
+        }
+    }
+
+    /// Reports one last time.
+    pub(crate) fn finish(&self) {
+        if let Some(report) = &self.monitor.report {
+            report(&self.progress());
+        }
+    }
+
+    fn progress(&self) -> Progress {
+        let per_thread: Vec<u64> = self
+            .per_thread
+            .iter()
+            .map(|indices| indices.load(Ordering::Relaxed))
+            .collect();
+        Progress {
+            done: self.resumed + per_thread.iter().sum::<u64>(),
+            total: self.total,
+            resumed: self.resumed,
+            terms: self.terms,
+            per_thread,
+            elapsed: self.start.elapsed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        digits::{DigitsError, digits_monitored},
+        formulas::{self, Constant},
+        wide::Sum,
+    };
+
+    #[test]
+    fn clones_share_cancellation() {
+        let token = CancelToken::new();
+        let monitor = Monitor::new().with_cancel(token.clone());
+        assert!(!monitor.cancel_token().is_cancelled());
+        token.cancel();
+        assert!(monitor.clone().cancel_token().is_cancelled());
+        assert!(Tracker::new(&monitor, 1, 0, 1, 1).cancelled());
+    }
+
+    #[test]
+    fn tracker_counts_every_thread() {
+        let (sender, receiver) = mpsc::channel();
+        let monitor = Monitor::new()
+            .with_channel(sender)
+            .with_interval(Duration::from_secs(3600));
+        let tracker = Tracker::new(&monitor, 100, 30, 7, 3);
+        tracker.record(0, 10);
+        tracker.record(2, 5);
+        tracker.record(0, 1);
+        // Nothing is reported until the interval has passed
+        assert!(receiver.try_recv().is_err());
+        tracker.finish();
+        let progress = receiver.try_recv().unwrap();
+        assert_eq!(progress.per_thread, [11, 0, 5]);
+        assert_eq!((progress.done, progress.total), (46, 100));
+        assert_eq!((progress.resumed, progress.terms), (30, 7));
+        assert_eq!(progress.fraction(), 0.46);
+    }
+
+    #[test]
+    fn eta_follows_the_rate_since_starting() {
+        let mut progress = Progress {
+            done: 30,
+            total: 100,
+            resumed: 30,
+            terms: 7,
+            per_thread: vec![0],
+            elapsed: Duration::from_secs(5),
+        };
+        assert_eq!(progress.eta(), None);
+        progress.done = 40;
+        assert_eq!(progress.eta(), Some(Duration::from_secs(30)));
+        // More resumed than the total, as from a checkpoint for more indices
+        progress.resumed = 120;
+        progress.done = 130;
+        assert_eq!(progress.eta(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn cancelling_stops_an_evaluation() {
+        let cancel = CancelToken::new();
+        let monitor = Monitor::new()
+            .with_cancel(cancel.clone())
+            .with_interval(Duration::ZERO)
+            .with_callback(move |_| cancel.cancel());
+        let formula = formulas::bellards_pi().with_chunk(16);
+        let evaluation = formula.evaluate_monitored::<Sum<3>>(100_000, 2, 3, &monitor);
+        assert_eq!(evaluation.unwrap_err(), Cancelled);
+        assert_eq!(
+            digits_monitored(Constant::Pi, 100_000, 8, 16, &monitor),
+            Err(DigitsError::Cancelled)
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    ops::RangeInclusive,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+};
+
+use crate::{
+    PolyFormula,
+    modular::{Barrett32, Reciprocal},
+    wide::{Accumulator, Fraction},
+};
+
+/// An odd denominator and the power of the base reduced modulo it, shared
+/// by every term of every formula that needs it at one index.
+enum Power {
+    Narrow(Barrett32, u64),
+    Wide(Reciprocal, u64),
+}
+
+/// The powers computed at one index, keyed by base, odd denominator and
+/// exponent.
+type Powers = Vec<((u32, u64, u64), Power)>;
+
+/// Evaluates every formula in `formulas` at `digit` to `words` words in one
+/// pass over the indices, on `threads` threads. Terms with the same odd
+/// denominator and power of the base at an index, in the same formula or
+/// different ones, share one modular exponentiation, leaving a
+/// multiplication and a division per term. Returns one sum per formula,
+/// equal to [`PolyFormula::evaluate_interleaved`] without fusion.
+///
+/// A power modulo a denominator below 2^32 is reduced with [`Barrett32`]
+/// when the first formula to need it at an index uses
+/// [`PowMethod::Barrett`] or [`PowMethod::Simd`], and every other power with
+/// that formula's method on a [`Reciprocal`]. Nothing is batched across
+/// lanes, so [`PowMethod::Simd`] runs as [`PowMethod::Barrett`] here. The
+/// chunk size is taken from the first formula.
+///
+/// [`PowMethod::Barrett`]: crate::modular::PowMethod::Barrett
+/// [`PowMethod::Simd`]: crate::modular::PowMethod::Simd
+pub fn evaluate_shared<const N_DEGREE: usize, const D_DEGREE: usize, S: Fraction>(
+    formulas: &[PolyFormula<N_DEGREE, D_DEGREE>],
+    digit: u32,
+    threads: usize,
+    words: usize,
+) -> Vec<S> {
+    let Some(first) = formulas.first() else {
+        return Vec::new();
+    };
+    let lasts: Vec<u32> = formulas
+        .iter()
+        .map(|formula| digit + formula.tail(digit, words).terms)
+        .collect();
+    let last = lasts.iter().copied().max().unwrap_or(digit);
+    let chunk = first.chunk;
+    let cursor = AtomicU64::new(0);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads.max(1))
+            .map(|_| {
+                let (cursor, lasts) = (&cursor, &lasts);
+                scope.spawn(move || {
+                    let mut sums: Vec<Accumulator<S>> =
+                        formulas.iter().map(|_| Accumulator::zero(words)).collect();
+                    loop {
+                        let start = cursor.fetch_add(chunk.into(), Ordering::Relaxed);
+                        let Ok(start) = u32::try_from(start) else {
+                            break;
+                        };
+                        if start > last {
+                            break;
+                        }
+                        let end = start.saturating_add(chunk - 1).min(last);
+                        sums = evaluate_shared_indices::<_, _, S>(
+                            formulas,
+                            lasts,
+                            digit,
+                            start..=end,
+                            sums,
+                        );
+                    }
+                    sums.iter().map(Accumulator::sum).collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles.into_iter().fold(
+            formulas.iter().map(|_| S::zero(words)).collect(),
+            |totals: Vec<S>, handle| {
+                let sums = handle.join().unwrap();
+                totals.into_iter().zip(sums).map(|(a, b)| a + b).collect()
+            },
+        )
+    })
+}
+
+/// Adds every term of each formula at each index in `indices` to its sum,
+/// skipping indices past the formula's last correction term in `lasts`.
+fn evaluate_shared_indices<const N_DEGREE: usize, const D_DEGREE: usize, S: Fraction>(
+    formulas: &[PolyFormula<N_DEGREE, D_DEGREE>],
+    lasts: &[u32],
+    digit: u32,
+    indices: RangeInclusive<u32>,
+    mut sums: Vec<Accumulator<S>>,
+) -> Vec<Accumulator<S>> {
+    let mut powers: Powers = Vec::new();
+    for i in indices {
+        powers.clear();
+        for ((formula, &last), sum) in formulas.iter().zip(lasts).zip(&mut sums) {
+            if i > last {
+                continue;
+            }
+            let words = sum.words();
+            let index = formula.index(digit, i);
+            for term in 0..formula.numerators.len() {
+                let (term_positive, numerator, denominator, scale) =
+                    formula.term_parts(term, index);
+                let (exponent, shift) = match formula.split_exponent(index, scale) {
+                    Ok(split) => split,
+                    Err(_) => {
+                        // Past the digit there is no power to share
+                        let value: S =
+                            formula.fraction(numerator, denominator, scale, index, words);
+                        add(sum, term_positive, value);
+                        continue;
+                    }
+                };
+
+                let key = (formula.base_log2, denominator, exponent);
+                let position = match powers.iter().position(|(found, _)| *found == key) {
+                    Some(position) => position,
+                    None => {
+                        let base = 1u64 << formula.base_log2;
+                        let power = match formula.narrow_modulus(denominator) {
+                            Some(modulus) => {
+                                let power = modulus.mod_pow_init(1, base, exponent);
+                                Power::Narrow(modulus, power)
+                            }
+                            None => {
+                                let reciprocal = Reciprocal::new(denominator);
+                                let power = formula.mod_pow_init(&reciprocal, 1, exponent);
+                                Power::Wide(reciprocal, power)
+                            }
+                        };
+                        powers.push((key, power));
+                        powers.len() - 1
+                    }
+                };
+
+                let value: S = match &powers[position].1 {
+                    Power::Narrow(modulus, power) => {
+                        let init = numerator % denominator;
+                        let residue = modulus.div_rem(init * power).1;
+                        S::from_msd(words, modulus.mul_pow2(residue, shift)) / modulus
+                    }
+                    Power::Wide(reciprocal, power) => {
+                        let init = numerator % reciprocal;
+                        let mut residue = (u128::from(init) * u128::from(*power)) % reciprocal;
+                        if shift > 0 {
+                            residue = (u128::from(residue) << shift) % reciprocal;
+                        }
+                        S::from_msd(words, residue) / reciprocal
+                    }
+                };
+                add(sum, term_positive, value);
+            }
+        }
+    }
+    sums
+}
+
+fn add<S: Fraction>(sum: &mut Accumulator<S>, positive: bool, value: S) {
+    if positive {
+        *sum += value;
+    } else {
+        *sum -= value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        formulas,
+        modular::PowMethod,
+        polynomial::Polynomial,
+        wide::{DynSum, Sum},
+    };
+
+    /// A base-16 formula over the same `8n + j` denominators as
+    /// [`formulas::bailey_borwein_plouffe_pi`], and one of its own.
+    fn companion() -> PolyFormula<1, 2> {
+        PolyFormula::new(
+            false,
+            4,
+            vec![
+                Polynomial::new([8]),
+                Polynomial::new([4]),
+                Polynomial::new([-3]),
+                Polynomial::new([1]),
+            ],
+            vec![
+                Polynomial::new([1, 8]),
+                Polynomial::new([2, 8]),
+                Polynomial::new([5, 8]),
+                Polynomial::new([7, 8]),
+            ],
+        )
+    }
+
+    #[test]
+    fn shared_matches_interleaved() {
+        for pow_method in [PowMethod::Barrett, PowMethod::Reciprocal, PowMethod::Simd] {
+            let formulas = [
+                formulas::bailey_borwein_plouffe_pi().with_pow_method(pow_method),
+                companion().with_pow_method(PowMethod::Montgomery),
+            ];
+            for digit in [0, 1, 9, 1_000, 5_000] {
+                let shared: Vec<Sum<3>> = evaluate_shared(&formulas, digit, 2, 3);
+                let dynamic: Vec<DynSum> = evaluate_shared(&formulas, digit, 1, 4);
+                for ((formula, shared), dynamic) in formulas.iter().zip(shared).zip(dynamic) {
+                    let expected: Sum<3> = formula.evaluate_interleaved(digit, 3);
+                    assert_eq!(shared.digits, expected.digits, "{pow_method} at {digit}");
+                    let expected: DynSum = formula.evaluate_interleaved(digit, 4);
+                    assert_eq!(dynamic.digits, expected.digits, "{pow_method} at {digit}");
+                }
+            }
+        }
+    }
+}
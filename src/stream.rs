@@ -0,0 +1,211 @@
+use std::{
+    collections::VecDeque,
+    sync::mpsc,
+    thread::{self, JoinHandle},
+};
+
+use crate::{
+    PolyFormula,
+    digits::{Digits, DigitsError, digits_monitored},
+    formulas::Constant,
+    progress::{CancelToken, Monitor},
+};
+
+/// Digits yielded from each window by default.
+const WINDOW: u32 = 256;
+
+/// Digits each window computes past the ones it yields, to be checked against
+/// the start of the next.
+const OVERLAP: u32 = 16;
+
+/// Windows computed ahead of the one being read by default.
+const RUN_AHEAD: usize = 2;
+
+/// Digits of a constant read one at a time from a starting position, from
+/// [`digits_from`]. Each window of digits is computed with
+/// [`digits_monitored`] on a background thread, which runs ahead of the
+/// reader by a few windows. Consecutive windows overlap, and the stream ends
+/// with [`DigitsError::Inconsistent`] if they disagree.
+///
+/// Dropping the stream cancels the window being computed.
+pub struct DigitStream<const N_DEGREE: usize, const D_DEGREE: usize> {
+    formula: PolyFormula<N_DEGREE, D_DEGREE>,
+    /// Position of the next digit yielded.
+    position: u64,
+    radix: u32,
+    window: u32,
+    run_ahead: usize,
+    /// Digits of the current window not yet yielded.
+    buffer: VecDeque<u8>,
+    /// Digits the last window computed past the ones it yielded.
+    overlap: Vec<u8>,
+    windows: Option<mpsc::Receiver<Result<Digits, DigitsError>>>,
+    producer: Option<JoinHandle<()>>,
+    cancel: CancelToken,
+    failed: bool,
+}
+
+/// Streams the digits in base `radix` of the constant given by `formula`,
+/// from `position` digits after the radix point onward.
+pub fn digits_from<const N_DEGREE: usize, const D_DEGREE: usize>(
+    formula: impl Into<PolyFormula<N_DEGREE, D_DEGREE>>,
+    position: u64,
+    radix: u32,
+) -> DigitStream<N_DEGREE, D_DEGREE> {
+    DigitStream {
+        formula: formula.into(),
+        position,
+        radix,
+        window: WINDOW,
+        run_ahead: RUN_AHEAD,
+        buffer: VecDeque::new(),
+        overlap: Vec::new(),
+        windows: None,
+        producer: None,
+        cancel: CancelToken::new(),
+        failed: false,
+    }
+}
+
+impl Constant {
+    /// Streams the hexadecimal digits of the constant from `position` digits
+    /// after the radix point onward.
+    pub fn digits_from(self, position: u64) -> DigitStream<1, 2> {
+        digits_from(self, position, 16)
+    }
+}
+
+impl<const N_DEGREE: usize, const D_DEGREE: usize> DigitStream<N_DEGREE, D_DEGREE> {
+    /// Sets the number of digits yielded from each window. Larger windows
+    /// cost little more to compute, since most of the work is spent on
+    /// exponentiation, but take longer to produce the first digit. Has no
+    /// effect once reading has started.
+    pub fn with_window(mut self, window: u32) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    /// Sets the number of windows computed ahead of the one being read. Has
+    /// no effect once reading has started.
+    pub fn with_run_ahead(mut self, windows: usize) -> Self {
+        self.run_ahead = windows;
+        self
+    }
+
+    /// Starts computing windows from the current position on a background
+    /// thread.
+    fn start(&mut self) -> &mpsc::Receiver<Result<Digits, DigitsError>> {
+        let (sender, windows) = mpsc::sync_channel(self.run_ahead);
+        let formula = self.formula.clone();
+        let (position, radix, window) = (self.position, self.radix, self.window);
+        let monitor = Monitor::new().with_cancel(self.cancel.clone());
+        self.producer = Some(thread::spawn(move || {
+            let mut start = Some(position);
+            while let Some(position) = start {
+                let count = window + OVERLAP;
+                let digits = digits_monitored(formula.clone(), position, count, radix, &monitor);
+                let failed = digits.is_err();
+                // Stop once the stream is dropped or nothing more can follow
+                if sender.send(digits).is_err() || failed {
+                    break;
+                }
+                start = position.checked_add(window.into());
+                if start.is_none() {
+                    // The next window would start past the last position
+                    let _ = sender.send(Err(DigitsError::PositionTooLarge(u64::MAX)));
+                }
+            }
+        }));
+        self.windows.insert(windows)
+    }
+
+    /// Takes the next window, checking that it agrees with the digits the
+    /// last one computed past its end.
+    fn next_window(&mut self) -> Result<(), DigitsError> {
+        let windows = match &self.windows {
+            Some(windows) => windows,
+            None => self.start(),
+        };
+        let digits = match windows.recv() {
+            Ok(digits) => digits?,
+            // The producer only hangs up before sending an error if it panicked
+            Err(_) => match self.producer.take().map(JoinHandle::join) {
+                Some(Err(panic)) => std::panic::resume_unwind(panic),
+                _ => unreachable!("the producer stopped without an error"),
+            },
+        };
+        if let Some(offset) = self
+            .overlap
+            .iter()
+            .zip(&digits.values)
+            .position(|(last, next)| last != next)
+        {
+            return Err(DigitsError::Inconsistent {
+                position: digits.position + offset as u64,
+            });
+        }
+        let (values, overlap) = digits.values.split_at(self.window as usize);
+        self.buffer.extend(values);
+        self.overlap = overlap.to_vec();
+        Ok(())
+    }
+}
+
+impl<const N_DEGREE: usize, const D_DEGREE: usize> Iterator for DigitStream<N_DEGREE, D_DEGREE> {
+    type Item = Result<u8, DigitsError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        if self.buffer.is_empty()
+            && let Err(err) = self.next_window()
+        {
+            self.failed = true;
+            return Some(Err(err));
+        }
+        let digit = self.buffer.pop_front()?;
+        self.position += 1;
+        Some(Ok(digit))
+    }
+}
+
+impl<const N_DEGREE: usize, const D_DEGREE: usize> Drop for DigitStream<N_DEGREE, D_DEGREE> {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+        // Unblocks the producer if it is waiting for room to send
+        self.windows = None;
+        if let Some(producer) = self.producer.take() {
+            let _ = producer.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{digits::digits, formulas};
+
+    #[test]
+    fn small_windows_match_digits() {
+        let formula = formulas::bellards_pi();
+        for (position, window, run_ahead) in [(0, 1, 0), (3, 5, 1), (1_000, 7, 2)] {
+            let count = 4 * window + 3;
+            let streamed: Vec<u8> = digits_from(formula.clone(), position, 16)
+                .with_window(window)
+                .with_run_ahead(run_ahead)
+                .take(count as usize)
+                .collect::<Result<_, _>>()
+                .unwrap();
+            let expected = digits(formula.clone(), position, count, 16).unwrap();
+            assert_eq!(streamed, expected.values, "window {window} from {position}");
+        }
+
+        let binary: Vec<u8> = digits_from(formula.clone(), 10, 2)
+            .with_window(3)
+            .take(20)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(binary, digits(formula, 10, 20, 2).unwrap().values);
+    }
+}
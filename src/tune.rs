@@ -0,0 +1,259 @@
+use std::{
+    fs, io,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{PARALLEL_CHUNK, PolyFormula, modular::PowMethod, progress::Monitor, wide::Fraction};
+
+/// File the tuning is saved to and loaded from, in the working directory,
+/// unless the `DROPLET_TUNING` environment variable names another.
+pub const TUNING_FILE: &str = "droplet-tuning.txt";
+
+/// First line of every tuning file, naming the format version.
+const HEADER: &str = "droplet tuning 1";
+
+/// Exponentiation methods compared by [`PolyFormula::autotune`].
+const POW_METHODS: [PowMethod; 6] = [
+    PowMethod::Barrett,
+    PowMethod::Reciprocal,
+    PowMethod::Pow2,
+    PowMethod::Montgomery,
+    PowMethod::Float,
+    PowMethod::Simd,
+];
+
+/// Chunk sizes compared by [`PolyFormula::autotune`], smallest first.
+const CHUNKS: [u32; 4] = [1 << 10, 1 << 12, 1 << 14, 1 << 16];
+
+/// Time each thread spends on a timed window, at the predicted rate.
+const WINDOW_TIME: Duration = Duration::from_millis(200);
+
+/// Chunks each thread claims in a timed window at least, so the window
+/// exercises the scheduling it is timing.
+const WINDOW_CHUNKS: u64 = 4;
+
+/// Shortest time per index, in seconds, a window is sized for, so an
+/// estimate of zero still gives a window that fits in memory and time.
+const MIN_INDEX_TIME: f64 = 1e-9;
+
+/// How much faster a later candidate must be to displace an earlier one,
+/// so noise does not decide between options that perform the same.
+const MARGIN: f64 = 1.02;
+
+/// How to evaluate on this machine, chosen by
+/// [`PolyFormula::autotune`]. None of it affects the values computed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tuning {
+    pub pow_method: PowMethod,
+    pub threads: usize,
+    /// Number of consecutive indices a thread claims at a time.
+    pub chunk: u32,
+}
+
+impl Default for Tuning {
+    /// The default exponentiation method on every available thread.
+    fn default() -> Self {
+        Self {
+            pow_method: PowMethod::default(),
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            chunk: PARALLEL_CHUNK,
+        }
+    }
+}
+
+impl std::fmt::Display for Tuning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} on {} threads in chunks of {}",
+            self.pow_method, self.threads, self.chunk
+        )
+    }
+}
+
+impl Tuning {
+    /// Where the tuning is saved: `DROPLET_TUNING` if set, or else
+    /// [`TUNING_FILE`].
+    pub fn path() -> PathBuf {
+        std::env::var_os("DROPLET_TUNING").map_or_else(|| TUNING_FILE.into(), PathBuf::from)
+    }
+
+    /// The tuning saved at [`Tuning::path`] when first asked for, or the
+    /// defaults if there is none or it cannot be read. Every
+    /// [`PolyFormula`] starts out with it.
+    pub fn current() -> Self {
+        static CURRENT: OnceLock<Tuning> = OnceLock::new();
+
+        *CURRENT.get_or_init(|| Self::load(Self::path()).unwrap_or_default())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let contents = [
+            HEADER.to_string(),
+            format!("pow_method {}", self.pow_method),
+            format!("threads {}", self.threads),
+            format!("chunk {}", self.chunk),
+        ]
+        .join("\n");
+        fs::write(path, contents + "\n")
+    }
+
+    fn parse(contents: &str) -> io::Result<Self> {
+        let malformed = |line: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("malformed tuning line: {line}"),
+            )
+        };
+        let mut lines = contents.lines();
+        let header = lines.next().unwrap_or_default();
+        if header != HEADER {
+            return Err(malformed(header));
+        }
+        let mut field = |key: &str| -> io::Result<&str> {
+            let line = lines.next().unwrap_or_default();
+            match line.split_once(' ') {
+                Some((found, value)) if found == key => Ok(value),
+                _ => Err(malformed(line)),
+            }
+        };
+
+        let pow_method = field("pow_method")?;
+        let pow_method = pow_method.parse().map_err(|_| malformed(pow_method))?;
+        let threads = field("threads")?;
+        let threads = match threads.parse() {
+            Ok(threads) if threads > 0 => threads,
+            _ => return Err(malformed(threads)),
+        };
+        let chunk = field("chunk")?;
+        let chunk = match chunk.parse() {
+            Ok(chunk) if chunk > 0 => chunk,
+            _ => return Err(malformed(chunk)),
+        };
+        Ok(Self {
+            pow_method,
+            threads,
+            chunk,
+        })
+    }
+}
+
+/// The candidate taking the least time, keeping an earlier one unless a
+/// later one is faster by more than [`MARGIN`].
+fn fastest<T: Copy>(candidates: &[T], mut time: impl FnMut(T) -> f64) -> T {
+    let mut best = (candidates[0], time(candidates[0]));
+    for &candidate in &candidates[1..] {
+        let elapsed = time(candidate);
+        if elapsed * MARGIN < best.1 {
+            best = (candidate, elapsed);
+        }
+    }
+    best.0
+}
+
+impl<const N_DEGREE: usize, const D_DEGREE: usize> PolyFormula<N_DEGREE, D_DEGREE> {
+    /// Chooses how to evaluate at `digit` to `words` words on this machine,
+    /// taking a few seconds: the exponentiation method predicted to be
+    /// fastest by [`PolyFormula::predict`], then the thread count and
+    /// chunk size that sum a window of indices fastest with it. Save the
+    /// result with [`Tuning::save`] for later evaluations to pick it up.
+    pub fn autotune<S: Fraction>(&self, digit: u32, words: usize) -> Tuning {
+        let predict = |pow_method| {
+            self.clone()
+                .with_pow_method(pow_method)
+                .predict::<S>(digit, 1, words)
+                .cpu_time
+                .as_secs_f64()
+        };
+        let pow_method = fastest(&POW_METHODS, predict);
+        let index_time = predict(pow_method) / (f64::from(digit) + 1.0);
+
+        let available = Tuning::default().threads;
+        let thread_counts: Vec<usize> = (0..usize::BITS)
+            .map(|shift| 1 << shift)
+            .take_while(|&threads| threads < available)
+            .chain([available])
+            .collect();
+        let formula = self.clone().with_pow_method(pow_method);
+        let threads = fastest(&thread_counts, |threads| {
+            formula.time_window::<S>(digit, threads, words, index_time)
+        });
+        let chunk = fastest(&CHUNKS, |chunk| {
+            let formula = formula.clone().with_chunk(chunk);
+            formula.time_window::<S>(digit, threads, words, index_time)
+        });
+
+        Tuning {
+            pow_method,
+            threads,
+            chunk,
+        }
+    }
+
+    /// Sums a window of indices in the middle of those below `digit` on
+    /// `threads` threads, sized to keep each of them busy for about
+    /// [`WINDOW_TIME`] at `index_time` seconds per index. Returns the time
+    /// taken per index.
+    fn time_window<S: Fraction>(
+        &self,
+        digit: u32,
+        threads: usize,
+        words: usize,
+        index_time: f64,
+    ) -> f64 {
+        let per_thread = (WINDOW_TIME.as_secs_f64() / index_time.max(MIN_INDEX_TIME)) as u64;
+        let per_thread = per_thread.max(WINDOW_CHUNKS * u64::from(self.chunk));
+        let window = per_thread
+            .saturating_mul(threads as u64)
+            .min(u64::from(digit) + 1) as u32;
+        let first = (digit / 2).min(digit - (window - 1));
+        let last = first + (window - 1);
+
+        let timer = Instant::now();
+        let sum: S = self
+            .evaluate_scheduled(digit, first..=last, threads, words, &Monitor::new())
+            .expect("a new monitor is never cancelled");
+        std::hint::black_box(sum);
+        timer.elapsed().as_secs_f64() / f64::from(window)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tuning_round_trips_through_a_file() {
+        let path = std::env::temp_dir().join(format!("droplet-tuning-{}.txt", std::process::id()));
+        for pow_method in POW_METHODS {
+            let tuning = Tuning {
+                pow_method,
+                threads: 3,
+                chunk: 1 << 12,
+            };
+            tuning.save(&path).unwrap();
+            assert_eq!(Tuning::load(&path).unwrap(), tuning);
+        }
+        fs::remove_file(&path).unwrap();
+
+        for malformed in [
+            "",
+            "droplet tuning 0\npow_method barrett\nthreads 1\nchunk 1\n",
+            "droplet tuning 1\npow_method fast\nthreads 1\nchunk 1\n",
+            "droplet tuning 1\npow_method barrett\nthreads 0\nchunk 1\n",
+            "droplet tuning 1\npow_method barrett\nchunk 1\nthreads 1\n",
+            "droplet tuning 1\npow_method barrett\nthreads 1\n",
+        ] {
+            let err = Tuning::parse(malformed).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{malformed:?}");
+        }
+    }
+}
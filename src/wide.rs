@@ -1,6 +1,75 @@
-use crate::modular::Reciprocal;
+use crate::modular::{Barrett32, Modulus128, Reciprocal};
 
-#[derive(Debug)]
+/// Fixed-point values the evaluation accumulates into: an integer word
+/// followed by fraction words, most significant first. [`Sum`] fixes the
+/// width at compile time and [`DynSum`] at run time; both share the same
+/// arithmetic.
+pub trait Fraction:
+    Clone
+    + Send
+    + 'static
+    + std::ops::Add<Output = Self>
+    + std::ops::Sub<Output = Self>
+    + std::ops::Shr<u32, Output = Self>
+    + for<'a> std::ops::Div<&'a Reciprocal, Output = Self>
+    + for<'a> std::ops::Div<&'a Barrett32, Output = Self>
+{
+    /// Limbs of an [`Accumulator`] of this width, kept inline when the
+    /// width is known at compile time.
+    type Limbs: AsRef<[i128]> + AsMut<[i128]> + std::fmt::Debug + Send;
+
+    /// Number of words in total when the type fixes it, as [`Sum`] does.
+    const WORDS: Option<usize>;
+
+    /// Zero with `words` words in total.
+    ///
+    /// # Panics
+    ///
+    /// If `words` differs from [`Fraction::WORDS`] when that is fixed.
+    fn zero(words: usize) -> Self;
+
+    /// Zeroed [`Accumulator`] limbs for `words` words.
+    ///
+    /// # Panics
+    ///
+    /// If `words` differs from [`Fraction::WORDS`] when that is fixed.
+    fn zero_limbs(words: usize) -> Self::Limbs;
+
+    fn digits(&self) -> &[u64];
+
+    fn digits_mut(&mut self) -> &mut [u64];
+
+    fn words(&self) -> usize {
+        self.digits().len()
+    }
+
+    fn from_msd(words: usize, high: u64) -> Self {
+        let mut output = Self::zero(words);
+        output.digits_mut()[0] = high;
+        output
+    }
+
+    fn from_lsd(words: usize, low: u64) -> Self {
+        let mut output = Self::zero(words);
+        output.digits_mut()[words - 1] = low;
+        output
+    }
+
+    /// Computes `numerator / divisor` for `numerator < divisor`.
+    fn from_ratio(words: usize, numerator: u128, divisor: &Modulus128) -> Self {
+        let mut output = Self::zero(words);
+        output.digits_mut()[1..]
+            .iter_mut()
+            .fold(numerator, |remainder, digit| {
+                let (quotient, remainder) = divisor.div_rem(remainder, 0);
+                *digit = quotient;
+                remainder
+            });
+        output
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
 pub struct Sum<const D: usize> {
     pub digits: [u64; D],
 }
@@ -11,94 +80,300 @@ impl<const D: usize> Sum<D> {
     }
 
     pub fn from_msd(high: u64) -> Self {
-        let mut digits = [0; D];
-        digits[0] = high;
-        Self { digits }
+        <Self as Fraction>::from_msd(D, high)
     }
 
     pub fn from_lsd(low: u64) -> Self {
-        let mut digits = [0; D];
-        digits[D - 1] = low;
-        Self { digits }
+        <Self as Fraction>::from_lsd(D, low)
+    }
+
+    /// Computes `numerator / divisor` for `numerator < divisor`.
+    pub fn from_ratio(numerator: u128, divisor: &Modulus128) -> Self {
+        <Self as Fraction>::from_ratio(D, numerator, divisor)
     }
 }
 
-impl<const D: usize> std::ops::Div<&Reciprocal> for Sum<D> {
-    type Output = Self;
+impl<const D: usize> Fraction for Sum<D> {
+    type Limbs = [i128; D];
 
-    fn div(self, rhs: &Reciprocal) -> Self::Output {
-        let mut output = Self::zero();
-        self.digits
-            .iter()
-            .enumerate()
-            .fold(0u64, |remainder, (idx, word)| {
-                let dividend = (u128::from(remainder) << 64) + u128::from(*word);
-                let quotient = dividend / rhs;
-                let remainder = dividend % rhs;
-                output.digits[idx] = quotient;
-                remainder
-            });
-        output
+    const WORDS: Option<usize> = Some(D);
+
+    fn zero(words: usize) -> Self {
+        assert_eq!(words, D, "Sum<{D}> has {D} words");
+        Self::zero()
+    }
+
+    fn zero_limbs(words: usize) -> Self::Limbs {
+        assert_eq!(words, D, "Sum<{D}> has {D} words");
+        [0; D]
+    }
+
+    fn digits(&self) -> &[u64] {
+        &self.digits
+    }
+
+    fn digits_mut(&mut self) -> &mut [u64] {
+        &mut self.digits
     }
 }
 
-impl<const D: usize> std::ops::Add for Sum<D> {
-    type Output = Self;
+/// A [`Sum`] whose width is chosen at run time, kept on the heap. Every term
+/// allocates, so [`Sum`] remains faster for widths known at compile time.
+#[derive(Clone, Debug)]
+pub struct DynSum {
+    pub digits: Vec<u64>,
+}
 
-    fn add(self, rhs: Self) -> Self::Output {
-        let mut output = Self::zero();
-        let lhs_iter = self.digits.into_iter();
-        let rhs_iter = rhs.digits.into_iter();
-        lhs_iter
-            .zip(rhs_iter)
-            .enumerate()
-            .rev()
-            .fold(false, |carry, (idx, (lhs, rhs))| {
-                let (sum, did_carry) = lhs.carrying_add(rhs, carry);
-                output.digits[idx] = sum;
-                did_carry
-            });
-        output
+impl DynSum {
+    pub fn zero(words: usize) -> Self {
+        Self {
+            digits: vec![0; words],
+        }
     }
 }
 
-impl<const D: usize> std::ops::Sub for Sum<D> {
-    type Output = Self;
+impl Fraction for DynSum {
+    type Limbs = Vec<i128>;
+
+    const WORDS: Option<usize> = None;
+
+    fn zero(words: usize) -> Self {
+        Self::zero(words)
+    }
+
+    fn zero_limbs(words: usize) -> Self::Limbs {
+        vec![0; words]
+    }
+
+    fn digits(&self) -> &[u64] {
+        &self.digits
+    }
+
+    fn digits_mut(&mut self) -> &mut [u64] {
+        &mut self.digits
+    }
+}
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        let mut output = Self::zero();
-        let lhs_iter = self.digits.into_iter();
-        let rhs_iter = rhs.digits.into_iter();
-        lhs_iter
-            .zip(rhs_iter)
+/// A [`Fraction`] that defers carry propagation. Each limb is a signed
+/// running total of the words added to or subtracted from that position, and
+/// carries are only resolved by [`Accumulator::sum`]. A limb moves by less
+/// than 2^64 per operation, so 2^63 operations can be accumulated before
+/// normalizing.
+#[derive(Debug)]
+pub struct Accumulator<S: Fraction> {
+    limbs: S::Limbs,
+}
+
+impl<S: Fraction> Accumulator<S> {
+    pub fn zero(words: usize) -> Self {
+        Self {
+            limbs: S::zero_limbs(words),
+        }
+    }
+
+    pub fn words(&self) -> usize {
+        self.limbs.as_ref().len()
+    }
+
+    /// Propagates the deferred carries, giving the same value as adding and
+    /// subtracting every operand directly.
+    pub fn sum(&self) -> S {
+        let mut output = S::zero(self.words());
+        let digits = output.digits_mut();
+        self.limbs
+            .as_ref()
+            .iter()
             .enumerate()
             .rev()
-            .fold(false, |carry, (idx, (lhs, rhs))| {
-                let (diff, did_borrow) = lhs.borrowing_sub(rhs, carry);
-                output.digits[idx] = diff;
-                did_borrow
+            .fold(0i128, |carry, (idx, limb)| {
+                let total = limb.wrapping_add(carry);
+                digits[idx] = total as u64;
+                total >> 64
             });
         output
     }
 }
 
-impl<const D: usize> std::ops::Shr<u32> for Sum<D> {
-    type Output = Self;
-    
-    fn shr(self, rhs: u32) -> Self::Output {
-        let mut output = Self::zero();
-        let words = (rhs / 64) as usize;
-        let shift = rhs % 64;
-        let hi_mask = if shift > 0 { u64::MAX } else { 0 };
-        if words < D {
-            output.digits[words] = self.digits[0] >> shift;
+impl<S: Fraction> std::ops::AddAssign<S> for Accumulator<S> {
+    fn add_assign(&mut self, rhs: S) {
+        debug_assert_eq!(self.words(), rhs.words());
+        for (limb, &word) in self.limbs.as_mut().iter_mut().zip(rhs.digits()) {
+            *limb += i128::from(word);
         }
-        for idx in (words + 1)..D {
-            let hi_bits = self.digits[idx - words - 1] << (64 - shift);
-            let lo_bits = self.digits[idx - words] >> shift;
-            let word = (hi_bits & hi_mask) | lo_bits;
-            output.digits[idx] = word;
+    }
+}
+
+impl<S: Fraction> std::ops::SubAssign<S> for Accumulator<S> {
+    fn sub_assign(&mut self, rhs: S) {
+        debug_assert_eq!(self.words(), rhs.words());
+        for (limb, &word) in self.limbs.as_mut().iter_mut().zip(rhs.digits()) {
+            *limb -= i128::from(word);
         }
-        output
+    }
+}
+
+fn div_reciprocal(digits: &mut [u64], rhs: &Reciprocal) {
+    digits.iter_mut().fold(0u64, |remainder, word| {
+        let dividend = (u128::from(remainder) << 64) + u128::from(*word);
+        let (quotient, remainder) = rhs.div_rem(dividend);
+        *word = quotient;
+        remainder
+    });
+}
+
+fn div_barrett(digits: &mut [u64], rhs: &Barrett32) {
+    digits.iter_mut().fold(0u64, |remainder, word| {
+        // The divisor is below 2^32, so divide each word in halves
+        let (quotient_hi, remainder) = rhs.div_rem((remainder << 32) | (*word >> 32));
+        let (quotient_lo, remainder) = rhs.div_rem((remainder << 32) | (*word & 0xffff_ffff));
+        *word = (quotient_hi << 32) | quotient_lo;
+        remainder
+    });
+}
+
+fn add_assign(lhs: &mut [u64], rhs: &[u64]) {
+    debug_assert_eq!(lhs.len(), rhs.len());
+    lhs.iter_mut()
+        .zip(rhs)
+        .rev()
+        .fold(false, |carry, (lhs, &rhs)| {
+            let (sum, did_carry) = lhs.carrying_add(rhs, carry);
+            *lhs = sum;
+            did_carry
+        });
+}
+
+fn sub_assign(lhs: &mut [u64], rhs: &[u64]) {
+    debug_assert_eq!(lhs.len(), rhs.len());
+    lhs.iter_mut()
+        .zip(rhs)
+        .rev()
+        .fold(false, |carry, (lhs, &rhs)| {
+            let (diff, did_borrow) = lhs.borrowing_sub(rhs, carry);
+            *lhs = diff;
+            did_borrow
+        });
+}
+
+fn shr_assign(digits: &mut [u64], rhs: u32) {
+    let len = digits.len();
+    let words = (rhs / 64) as usize;
+    let shift = rhs % 64;
+    let hi_mask = if shift > 0 { u64::MAX } else { 0 };
+    // From the least significant end, so every source word is still unshifted
+    for idx in (0..len).rev() {
+        digits[idx] = if idx < words {
+            0
+        } else if idx == words {
+            digits[0] >> shift
+        } else {
+            let hi_bits = digits[idx - words - 1].wrapping_shl(64 - shift);
+            let lo_bits = digits[idx - words] >> shift;
+            (hi_bits & hi_mask) | lo_bits
+        };
+    }
+}
+
+macro_rules! impl_arithmetic {
+    ($type:ty $(, $generic:ident)?) => {
+        impl<$(const $generic: usize)?> std::ops::Div<&Reciprocal> for $type {
+            type Output = Self;
+
+            fn div(mut self, rhs: &Reciprocal) -> Self::Output {
+                div_reciprocal(&mut self.digits, rhs);
+                self
+            }
+        }
+
+        impl<$(const $generic: usize)?> std::ops::Div<&Barrett32> for $type {
+            type Output = Self;
+
+            fn div(mut self, rhs: &Barrett32) -> Self::Output {
+                div_barrett(&mut self.digits, rhs);
+                self
+            }
+        }
+
+        impl<$(const $generic: usize)?> std::ops::Add for $type {
+            type Output = Self;
+
+            fn add(mut self, rhs: Self) -> Self::Output {
+                add_assign(&mut self.digits, &rhs.digits);
+                self
+            }
+        }
+
+        impl<$(const $generic: usize)?> std::ops::Sub for $type {
+            type Output = Self;
+
+            fn sub(mut self, rhs: Self) -> Self::Output {
+                sub_assign(&mut self.digits, &rhs.digits);
+                self
+            }
+        }
+
+        impl<$(const $generic: usize)?> std::ops::Shr<u32> for $type {
+            type Output = Self;
+
+            fn shr(mut self, rhs: u32) -> Self::Output {
+                shr_assign(&mut self.digits, rhs);
+                self
+            }
+        }
+    };
+}
+
+impl_arithmetic!(Sum<D>, D);
+impl_arithmetic!(DynSum);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accumulator_matches_direct_arithmetic() {
+        let mut state = 39u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let mut fixed = Accumulator::<Sum<3>>::zero(3);
+        let mut dynamic = Accumulator::<DynSum>::zero(3);
+        let mut expected = Sum::<3>::zero();
+        for step in 0..1000 {
+            let value = Sum {
+                digits: [next(), next(), next()],
+            };
+            if step % 3 == 0 {
+                fixed -= value;
+                dynamic -= DynSum {
+                    digits: value.digits.to_vec(),
+                };
+                expected = expected - value;
+            } else {
+                fixed += value;
+                dynamic += DynSum {
+                    digits: value.digits.to_vec(),
+                };
+                expected = expected + value;
+            }
+        }
+        assert_eq!(fixed.sum().digits, expected.digits);
+        assert_eq!(dynamic.sum().digits, expected.digits);
+    }
+
+    #[test]
+    #[should_panic(expected = "Sum<2> has 2 words")]
+    fn sum_has_one_width() {
+        let _: Accumulator<Sum<2>> = Accumulator::zero(3);
+    }
+
+    #[test]
+    fn only_sum_fixes_the_width() {
+        assert_eq!(<Sum<4> as Fraction>::WORDS, Some(4));
+        assert_eq!(<DynSum as Fraction>::WORDS, None);
+        let _: Accumulator<DynSum> = Accumulator::zero(7);
     }
 }
\ No newline at end of file
@@ -0,0 +1,70 @@
+use std::{
+    process::{Child, Command},
+    thread,
+    time::{Duration, Instant},
+};
+
+use droplet::{
+    digits::{digits, digits_with},
+    distributed::Coordinator,
+    formulas,
+    wide::DynSum,
+};
+
+/// Waits for `child` to exit, killing it if it takes longer than `timeout`.
+fn wait(mut child: Child, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if let Some(status) = child.try_wait().unwrap() {
+            return status.success();
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    let _ = child.kill();
+    false
+}
+
+#[test]
+fn workers_stay_connected_across_evaluations() {
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap().with_chunk(97);
+    let address = coordinator.local_addr().unwrap().to_string();
+    let workers: Vec<Child> = (0..2)
+        .map(|_| {
+            Command::new(env!("CARGO_BIN_EXE_worker"))
+                .args([address.as_str(), "2", "barrett"])
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+
+    let formula = formulas::bellards_pi();
+    for digit in [1_000, 2_500] {
+        let expected: DynSum = formula.evaluate_interleaved(digit, 3);
+        let evaluation = formula
+            .evaluate_distributed::<DynSum>(digit, 3, &coordinator)
+            .unwrap();
+        assert_eq!(evaluation.value.digits, expected.digits, "digit {digit}");
+    }
+    for position in [10, 4_000] {
+        let distributed = digits_with(
+            formula.clone(),
+            position,
+            20,
+            16,
+            |formula, digit, words, _| {
+                formula.evaluate_distributed::<DynSum>(digit, words, &coordinator)
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            distributed,
+            digits(formula.clone(), position, 20, 16).unwrap()
+        );
+    }
+
+    // Workers finish cleanly once the coordinator hangs up
+    drop(coordinator);
+    for worker in workers {
+        assert!(wait(worker, Duration::from_secs(10)));
+    }
+}